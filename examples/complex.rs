@@ -24,7 +24,7 @@ fn main() {
 
         println!("Frame of length {}", frame.len());
 
-        let mut file = fs::File::create(&format!("frame-{}.jpg", i)).unwrap();
+        let mut file = fs::File::create(format!("frame-{}.jpg", i)).unwrap();
         file.write_all(&frame[..]).unwrap();
     }
 }
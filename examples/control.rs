@@ -15,9 +15,9 @@ fn main() {
     let old = get_brightness();
 
     println!("Current value of brightness: {}", old);
-    camera.set_control(CID_BRIGHTNESS, &5).unwrap();
+    camera.set_control(CID_BRIGHTNESS, 5).unwrap();
     println!("New value of brightness: {}", get_brightness());
 
-    camera.set_control(CID_BRIGHTNESS, &old).unwrap();
+    camera.set_control(CID_BRIGHTNESS, old).unwrap();
     println!("Restoring old value: {}", get_brightness());
 }
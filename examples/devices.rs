@@ -0,0 +1,27 @@
+fn main() {
+    for device in rscam::devices().unwrap() {
+        let caps = &device.capabilities;
+
+        let kind = if caps.is_capture() {
+            "capture"
+        } else if caps.is_metadata() {
+            "metadata"
+        } else if caps.is_output() {
+            "output"
+        } else {
+            "other"
+        };
+
+        println!(
+            "{} ({}): {} [{}]",
+            device.path.display(),
+            kind,
+            caps.card,
+            caps.bus_info
+        );
+
+        for link in device.by_id.iter().chain(&device.by_path) {
+            println!("  {}", link.display());
+        }
+    }
+}
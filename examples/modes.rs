@@ -0,0 +1,6 @@
+fn main() {
+    let camera = rscam::new("/dev/video0").unwrap();
+    let modes = camera.modes().unwrap();
+
+    println!("{}", serde_json::to_string_pretty(&modes).unwrap());
+}
@@ -0,0 +1,101 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::{ready, Stream};
+use tokio::io::unix::AsyncFd;
+
+use crate::{Camera, Frame, Result};
+
+/// Streaming camera registered in the tokio reactor.
+/// It also implements `Stream` of frames, which ends after the first error
+/// (e.g. `Error::InvalidState` if not streaming).
+///
+/// ```no_run
+/// # async fn run() {
+/// use rscam::{AsyncCamera, Camera, Config};
+///
+/// let mut camera = Camera::new("/dev/video0").unwrap();
+/// camera.start(&Config::default()).unwrap();
+///
+/// let camera = AsyncCamera::new(camera).unwrap();
+///
+/// loop {
+///     let frame = camera.capture().await.unwrap();
+///     println!("Frame of length {}", frame.len());
+/// }
+/// # }
+/// ```
+pub struct AsyncCamera {
+    inner: AsyncFd<Camera>,
+    terminated: bool,
+}
+
+impl AsyncCamera {
+    /// Register the camera in the reactor of the current runtime.
+    ///
+    /// # Panics
+    /// If called outside of the tokio runtime.
+    pub fn new(camera: Camera) -> io::Result<AsyncCamera> {
+        Ok(AsyncCamera {
+            inner: AsyncFd::new(camera)?,
+            terminated: false,
+        })
+    }
+
+    /// Wait for the next frame.
+    ///
+    /// Returns `Error::InvalidState` if not streaming.
+    pub async fn capture(&self) -> Result<Frame> {
+        loop {
+            let mut guard = self.inner.readable().await?;
+
+            match self.inner.get_ref().try_capture()? {
+                Some(frame) => return Ok(frame),
+                None => guard.clear_ready(),
+            }
+        }
+    }
+
+    /// Get the registered camera, e.g. to change controls.
+    pub fn get_ref(&self) -> &Camera {
+        self.inner.get_ref()
+    }
+
+    /// Get the registered camera mutably, e.g. to restart streaming.
+    /// The fd must stay the same, the camera is still registered in the reactor.
+    pub fn get_mut(&mut self) -> &mut Camera {
+        self.inner.get_mut()
+    }
+
+    /// Deregister the camera from the reactor.
+    pub fn into_inner(self) -> Camera {
+        self.inner.into_inner()
+    }
+}
+
+impl Stream for AsyncCamera {
+    type Item = Result<Frame>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+
+        let result = loop {
+            let mut guard = match ready!(self.inner.poll_read_ready(cx)) {
+                Ok(guard) => guard,
+                Err(err) => break Err(err.into()),
+            };
+
+            match guard.get_inner().try_capture() {
+                Ok(Some(frame)) => return Poll::Ready(Some(Ok(frame))),
+                Ok(None) => guard.clear_ready(),
+                Err(err) => break Err(err),
+            }
+        };
+
+        self.terminated = true;
+        Poll::Ready(Some(result))
+    }
+}
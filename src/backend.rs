@@ -0,0 +1,62 @@
+use std::io;
+use std::os::raw::c_void;
+use std::os::unix::io::RawFd;
+use std::time::Duration;
+
+use crate::v4l2;
+
+/// Low-level access to devices, which is used by `Camera` (see `Camera::with_backend()`).
+///
+/// Requests, arguments and errors are the same as for the system calls on v4l2 devices,
+/// e.g. `ioctl()` returns `EAGAIN` if there is no captured buffer yet.
+pub trait Backend: Send + Sync {
+    /// Open the device. The descriptor must be a real one owned by the backend (e.g. an eventfd),
+    /// because `Camera` exposes it (see `AsFd`) to be registered in reactors.
+    fn open(&self, path: &str) -> io::Result<RawFd>;
+
+    fn close(&self, fd: RawFd) -> io::Result<()>;
+
+    /// # Safety
+    /// `arg` must point to the valid argument of the request.
+    unsafe fn ioctl(&self, fd: RawFd, request: usize, arg: *mut c_void) -> io::Result<()>;
+
+    /// Map the buffer, `offset` is got by `VIDIOC_QUERYBUF`.
+    fn mmap(&self, fd: RawFd, length: usize, offset: usize) -> io::Result<*mut u8>;
+
+    /// # Safety
+    /// The region must be mapped by `mmap()` and mustn't be used anymore.
+    unsafe fn munmap(&self, ptr: *mut u8, length: usize);
+
+    /// Wait until one of `events` (`POLLIN`, `POLLPRI`) occurs. Returns `false` on timeout.
+    fn poll(&self, fd: RawFd, events: i16, timeout: Option<Duration>) -> io::Result<bool>;
+}
+
+/// Real devices, accessed through *libv4l2* unless feature `no_wrapper` is enabled.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Kernel;
+
+impl Backend for Kernel {
+    fn open(&self, path: &str) -> io::Result<RawFd> {
+        v4l2::open(path)
+    }
+
+    fn close(&self, fd: RawFd) -> io::Result<()> {
+        v4l2::close(fd)
+    }
+
+    unsafe fn ioctl(&self, fd: RawFd, request: usize, arg: *mut c_void) -> io::Result<()> {
+        v4l2::ioctl(fd, request, arg)
+    }
+
+    fn mmap(&self, fd: RawFd, length: usize, offset: usize) -> io::Result<*mut u8> {
+        v4l2::mmap(length, fd, offset)
+    }
+
+    unsafe fn munmap(&self, ptr: *mut u8, length: usize) {
+        v4l2::munmap(ptr, length)
+    }
+
+    fn poll(&self, fd: RawFd, events: i16, timeout: Option<Duration>) -> io::Result<bool> {
+        v4l2::poll(fd, events, timeout)
+    }
+}
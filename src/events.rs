@@ -0,0 +1,202 @@
+use crate::v4l2;
+use crate::{ControlValue, Frame};
+
+/// Kind of events to subscribe to, see `Camera::subscribe()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventKind {
+    /// Changes of the value, the flags or the range of the control with the ID.
+    Control(u32),
+    /// Changes of the source connected to the input with the index (usually `0`),
+    /// e.g. another resolution of HDMI signal.
+    SourceChange(u32),
+    /// The last frame has been decoded or captured.
+    Eos,
+    /// Start of a new frame, before it's captured completely.
+    FrameSync,
+    /// Vertical sync.
+    VSync,
+}
+
+impl EventKind {
+    pub(crate) fn subscription(self) -> v4l2::EventSubscription {
+        match self {
+            EventKind::Control(id) => v4l2::EventSubscription::new(v4l2::EVENT_CTRL, id),
+            EventKind::SourceChange(input) => {
+                v4l2::EventSubscription::new(v4l2::EVENT_SOURCE_CHANGE, input)
+            }
+            EventKind::Eos => v4l2::EventSubscription::new(v4l2::EVENT_EOS, 0),
+            EventKind::FrameSync => v4l2::EventSubscription::new(v4l2::EVENT_FRAME_SYNC, 0),
+            EventKind::VSync => v4l2::EventSubscription::new(v4l2::EVENT_VSYNC, 0),
+        }
+    }
+}
+
+/// Event dequeued by `Camera::dequeue_event()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Event {
+    /// Number of events still pending after this one.
+    pub pending: u32,
+    /// Sequence number of the event, it's incremented for every event of the device.
+    pub sequence: u32,
+    /// Timestamp in microseconds using monotonically nondecreasing clock.
+    pub timestamp: u64,
+    pub data: EventData,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventData {
+    Control(ControlEvent),
+    /// The source has changed, see `EVENT_SRC_CH_*` constants for the changes.
+    /// The format should be negotiated again (e.g. by `Camera::reconfigure()`).
+    SourceChange {
+        input: u32,
+        changes: u32,
+    },
+    /// The end of stream, no frames will follow.
+    Eos,
+    /// The sequence number of the frame being received.
+    FrameSync(u32),
+    /// The field of the next frame, see `FIELD_*` constants.
+    VSync(u32),
+    /// Event of unsupported type (e.g. driver specific).
+    Unknown(u32),
+}
+
+/// Result of `Camera::capture_or_event()`.
+pub enum Captured {
+    Frame(Frame),
+    Event(Event),
+}
+
+/// Change of the control, see `EventKind::Control`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlEvent {
+    pub id: u32,
+    /// What has changed, see `EVENT_CTRL_CH_*` constants.
+    pub changes: u32,
+    /// The current value, `None` for controls without numeric value (e.g. strings).
+    pub value: Option<ControlValue>,
+    /// See `FLAG_*` constants for details.
+    pub flags: u32,
+    pub minimum: i32,
+    pub maximum: i32,
+    pub step: i32,
+    pub default: i32,
+}
+
+impl Event {
+    pub(crate) fn new(event: &v4l2::Event) -> Event {
+        let data = match event.etype {
+            v4l2::EVENT_CTRL => {
+                EventData::Control(ControlEvent::new(event.id, unsafe { &event.u.ctrl }))
+            }
+            v4l2::EVENT_SOURCE_CHANGE => EventData::SourceChange {
+                input: event.id,
+                changes: unsafe { event.u.src_change.changes },
+            },
+            v4l2::EVENT_EOS => EventData::Eos,
+            v4l2::EVENT_FRAME_SYNC => {
+                EventData::FrameSync(unsafe { event.u.frame_sync.frame_sequence })
+            }
+            v4l2::EVENT_VSYNC => EventData::VSync(u32::from(unsafe { event.u.vsync.field })),
+            etype => EventData::Unknown(etype),
+        };
+
+        let t = event.timestamp;
+
+        Event {
+            pending: event.pending,
+            sequence: event.sequence,
+            timestamp: 1_000_000 * (t.tv_sec as u64) + (t.tv_nsec as u64) / 1000,
+            data,
+        }
+    }
+}
+
+impl ControlEvent {
+    fn new(id: u32, ctrl: &v4l2::EventCtrl) -> ControlEvent {
+        let value = ctrl.value();
+
+        let value = match ctrl.ctype {
+            v4l2::CTRL_TYPE_INTEGER => Some(ControlValue::Integer(value as i32)),
+            v4l2::CTRL_TYPE_BOOLEAN => Some(ControlValue::Boolean(value != 0)),
+            v4l2::CTRL_TYPE_MENU | v4l2::CTRL_TYPE_INTEGER_MENU => {
+                Some(ControlValue::Menu(value as u32))
+            }
+            v4l2::CTRL_TYPE_INTEGER64 => Some(ControlValue::Integer64(value)),
+            v4l2::CTRL_TYPE_BITMASK => Some(ControlValue::Bitmask(value as u32)),
+            _ => None,
+        };
+
+        ControlEvent {
+            id,
+            changes: ctrl.changes,
+            value,
+            flags: ctrl.flags,
+            minimum: ctrl.minimum,
+            maximum: ctrl.maximum,
+            step: ctrl.step,
+            default: ctrl.default_value,
+        }
+    }
+}
+
+#[test]
+fn test_events() {
+    use crate::consts::*;
+
+    let mut raw = v4l2::Event::new();
+    raw.etype = v4l2::EVENT_CTRL;
+    raw.id = CID_EXPOSURE_ABSOLUTE;
+    raw.sequence = 3;
+    raw.timestamp.tv_sec = 2;
+    raw.timestamp.tv_nsec = 5_000;
+
+    raw.u.ctrl.changes = EVENT_CTRL_CH_VALUE;
+    raw.u.ctrl.ctype = v4l2::CTRL_TYPE_INTEGER;
+    raw.u.ctrl.value.value = -156;
+    raw.u.ctrl.maximum = 5000;
+
+    let event = Event::new(&raw);
+    assert_eq!(event.sequence, 3);
+    assert_eq!(event.timestamp, 2_000_005);
+
+    match event.data {
+        EventData::Control(ctrl) => {
+            assert_eq!(ctrl.id, CID_EXPOSURE_ABSOLUTE);
+            assert_eq!(ctrl.changes, EVENT_CTRL_CH_VALUE);
+            assert_eq!(ctrl.value, Some(ControlValue::Integer(-156)));
+            assert_eq!(ctrl.maximum, 5000);
+        }
+        data => panic!("unexpected {:?}", data),
+    }
+
+    raw.etype = v4l2::EVENT_SOURCE_CHANGE;
+    raw.id = 0;
+    raw.u.src_change.changes = EVENT_SRC_CH_RESOLUTION;
+    assert_eq!(
+        Event::new(&raw).data,
+        EventData::SourceChange {
+            input: 0,
+            changes: EVENT_SRC_CH_RESOLUTION
+        }
+    );
+
+    raw.etype = v4l2::EVENT_EOS;
+    assert_eq!(Event::new(&raw).data, EventData::Eos);
+
+    raw.etype = v4l2::EVENT_FRAME_SYNC;
+    raw.u.frame_sync.frame_sequence = 42;
+    assert_eq!(Event::new(&raw).data, EventData::FrameSync(42));
+
+    raw.etype = v4l2::EVENT_VSYNC;
+    raw.u.vsync.field = FIELD_BOTTOM as u8;
+    assert_eq!(Event::new(&raw).data, EventData::VSync(FIELD_BOTTOM));
+
+    raw.etype = 0x08000000;
+    assert_eq!(Event::new(&raw).data, EventData::Unknown(0x08000000));
+}
@@ -0,0 +1,591 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::consts::*;
+use crate::{ActualFormat, Config, Error, FormatInfo, Frame, IntervalInfo, Negotiation};
+use crate::{ResolutionInfo, Result, State};
+
+/// Virtual camera playing back recorded footage at the interval of `Config`.
+///
+/// Frames are neither skipped nor dropped: the frame is due at `sequence * interval` after
+/// `start()`, timestamps are the same, but counted from zero. After the last frame
+/// `Error::EndOfStream` is returned unless looping is enabled by `set_looping()`.
+///
+/// ```no_run
+/// use rscam::{Config, FileCamera};
+///
+/// let mut camera = FileCamera::jpeg_dir("footage").unwrap();
+///
+/// camera.start(&Config {
+///     interval: (1, 30),
+///     format: b"MJPG",
+///     resolution: camera.format().resolution,
+///     ..Default::default()
+/// }).unwrap();
+///
+/// let frame = camera.capture().unwrap();
+/// ```
+pub struct FileCamera {
+    footage: Footage,
+    format: ActualFormat,
+    looping: bool,
+    state: State,
+    interval: (u32, u32),
+    started: Instant,
+    /// Number of played frames, `Frame::sequence()` wraps like the kernel's 32-bit counter.
+    sequence: u64,
+}
+
+enum Footage {
+    /// Sorted paths to JPEG files.
+    Jpeg { files: Vec<PathBuf>, next: usize },
+    /// Frames without any headers.
+    Raw { file: BufReader<File> },
+    /// Frames are started with `FRAME` lines, `start` is the offset after the header.
+    Y4m { file: BufReader<File>, start: u64 },
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+/// Get the resolution from the SOF segment of JPEG.
+fn jpeg_resolution(data: &[u8]) -> Option<(u32, u32)> {
+    if !data.starts_with(&[0xff, 0xd8]) {
+        return None;
+    }
+
+    let mut pos = 2;
+
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xff {
+            return None;
+        }
+
+        let marker = data[pos + 1];
+
+        // Fill bytes.
+        if marker == 0xff {
+            pos += 1;
+            continue;
+        }
+
+        let len = usize::from(data[pos + 2]) << 8 | usize::from(data[pos + 3]);
+
+        match marker {
+            0xc0..=0xcf if marker != 0xc4 && marker != 0xc8 && marker != 0xcc => {
+                let sof = data.get(pos + 5..pos + 9)?;
+                let height = u32::from(sof[0]) << 8 | u32::from(sof[1]);
+                let width = u32::from(sof[2]) << 8 | u32::from(sof[3]);
+                return Some((width, height));
+            }
+            0xd9 | 0xda => return None,
+            _ => pos += 2 + len,
+        }
+    }
+
+    None
+}
+
+/// Parse parameters of the Y4M header (after `YUV4MPEG2`).
+fn y4m_format(header: &str) -> Result<ActualFormat> {
+    let mut resolution: (u32, u32) = (0, 0);
+    let mut field = FIELD_NONE;
+    let mut colorspace = "420jpeg";
+
+    for token in header.split_whitespace() {
+        if !token.is_char_boundary(1) {
+            continue;
+        }
+
+        let (tag, value) = token.split_at(1);
+
+        match tag {
+            "W" => resolution.0 = value.parse().map_err(|_| Error::BadFormat)?,
+            "H" => resolution.1 = value.parse().map_err(|_| Error::BadFormat)?,
+            "I" => {
+                field = match value {
+                    "t" => FIELD_INTERLACED_TB,
+                    "b" => FIELD_INTERLACED_BT,
+                    _ => FIELD_NONE,
+                }
+            }
+            "C" => colorspace = value,
+            _ => {}
+        }
+    }
+
+    let (width, height) = resolution;
+    let (cwidth, cheight) = (width.div_ceil(2), height.div_ceil(2));
+
+    let (format, chroma) = match colorspace {
+        "420jpeg" | "420paldv" | "420mpeg2" | "420" => (*b"YU12", cwidth.checked_mul(cheight)),
+        "422" => (*b"422P", cwidth.checked_mul(height)),
+        "mono" => (*b"GREY", Some(0)),
+        _ => return Err(Error::BadFormat),
+    };
+
+    if width == 0 || height == 0 {
+        return Err(Error::BadResolution);
+    }
+
+    let sizeimage = width
+        .checked_mul(height)
+        .zip(chroma.and_then(|c| c.checked_mul(2)))
+        .and_then(|(luma, chroma)| luma.checked_add(chroma))
+        .ok_or(Error::BadResolution)?;
+
+    Ok(ActualFormat {
+        resolution,
+        format,
+        field,
+        bytesperline: width,
+        sizeimage,
+        colorspace: 0,
+    })
+}
+
+impl FileCamera {
+    /// Play back JPEG files (`*.jpg` or `*.jpeg`) of the directory in order of their names.
+    /// The format is `MJPG`, the resolution is taken from the first file.
+    pub fn jpeg_dir<P: AsRef<Path>>(dir: P) -> Result<FileCamera> {
+        let mut files = vec![];
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let ext = path.extension().and_then(|e| e.to_str());
+
+            if ext.is_some_and(|e| e.eq_ignore_ascii_case("jpg") || e.eq_ignore_ascii_case("jpeg"))
+            {
+                files.push(path);
+            }
+        }
+
+        files.sort();
+
+        let first = files.first().ok_or_else(|| invalid_data("no JPEG files"))?;
+        let resolution =
+            jpeg_resolution(&fs::read(first)?).ok_or_else(|| invalid_data("bad JPEG"))?;
+
+        let mut sizeimage = 0;
+
+        for path in &files {
+            sizeimage = sizeimage.max(fs::metadata(path)?.len() as u32);
+        }
+
+        let format = ActualFormat {
+            resolution,
+            format: *b"MJPG",
+            field: FIELD_NONE,
+            bytesperline: 0,
+            sizeimage,
+            colorspace: 0,
+        };
+
+        Ok(FileCamera::new(Footage::Jpeg { files, next: 0 }, format))
+    }
+
+    /// Play back the raw dump of `YUYV` frames of the resolution.
+    pub fn yuyv<P: AsRef<Path>>(path: P, resolution: (u32, u32)) -> Result<FileCamera> {
+        let file = BufReader::new(File::open(path)?);
+
+        let bytesperline = resolution.0.checked_mul(2);
+        let sizeimage = bytesperline.and_then(|b| b.checked_mul(resolution.1));
+
+        let format = match (bytesperline, sizeimage) {
+            (Some(bytesperline), Some(sizeimage)) if sizeimage != 0 => ActualFormat {
+                resolution,
+                format: *b"YUYV",
+                field: FIELD_NONE,
+                bytesperline,
+                sizeimage,
+                colorspace: 0,
+            },
+            _ => return Err(Error::BadResolution),
+        };
+
+        Ok(FileCamera::new(Footage::Raw { file }, format))
+    }
+
+    /// Play back the Y4M file. The format is `YU12` (4:2:0), `422P` (4:2:2) or `GREY`,
+    /// the frame rate of the file is ignored.
+    pub fn y4m<P: AsRef<Path>>(path: P) -> Result<FileCamera> {
+        let mut file = BufReader::new(File::open(path)?);
+        let mut header = String::new();
+        file.read_line(&mut header)?;
+
+        let format = match header.strip_prefix("YUV4MPEG2 ") {
+            Some(params) => y4m_format(params)?,
+            None => return Err(invalid_data("not a Y4M file")),
+        };
+
+        let start = header.len() as u64;
+        Ok(FileCamera::new(Footage::Y4m { file, start }, format))
+    }
+
+    fn new(footage: Footage, format: ActualFormat) -> FileCamera {
+        FileCamera {
+            footage,
+            format,
+            looping: false,
+            state: State::Idle,
+            interval: (0, 0),
+            started: Instant::now(),
+            sequence: 0,
+        }
+    }
+
+    /// Start again from the first frame after the last one instead of `Error::EndOfStream`.
+    /// Default is `false`.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Get the format of the footage.
+    pub fn format(&self) -> ActualFormat {
+        self.format
+    }
+
+    /// Get detailed info about the format of the footage, it's the only one.
+    pub fn formats(&self) -> Vec<FormatInfo> {
+        let description = match &self.format.format {
+            b"MJPG" => "Motion-JPEG",
+            b"YUYV" => "YUYV 4:2:2",
+            b"YU12" => "Planar YUV 4:2:0",
+            b"422P" => "Planar YUV 4:2:2",
+            _ => "8-bit Greyscale",
+        };
+
+        vec![FormatInfo {
+            format: self.format.format,
+            description: description.into(),
+            compressed: self.format.format == *b"MJPG",
+            emulated: false,
+        }]
+    }
+
+    /// Get the resolution of the footage, it's the only one.
+    ///
+    /// Returns `Error::BadFormat` for another format.
+    pub fn resolutions(&self, format: [u8; 4]) -> Result<ResolutionInfo> {
+        if format != self.format.format {
+            return Err(Error::BadFormat);
+        }
+
+        Ok(ResolutionInfo::Discretes(vec![self.format.resolution]))
+    }
+
+    /// Any interval can be used, but intervals from 1ms to 1s by 1ms are reported.
+    ///
+    /// Returns `Error::BadFormat` or `Error::BadResolution` for other format and resolution.
+    pub fn intervals(&self, format: [u8; 4], resolution: (u32, u32)) -> Result<IntervalInfo> {
+        if format != self.format.format {
+            return Err(Error::BadFormat);
+        }
+
+        if resolution != self.format.resolution {
+            return Err(Error::BadResolution);
+        }
+
+        Ok(IntervalInfo::Stepwise {
+            min: (1, 1000),
+            max: (1, 1),
+            step: (1, 1000),
+        })
+    }
+
+    /// Get the interval of the last call of `start()`.
+    pub fn interval(&self) -> Option<(u32, u32)> {
+        match self.interval {
+            (0, _) => None,
+            interval => Some(interval),
+        }
+    }
+
+    /// Start playing back from the first frame.
+    ///
+    /// The format, the resolution and the field of the footage can't be changed, so they have
+    /// to be requested unless `Negotiation::Nearest` is used. The interval can be any.
+    ///
+    /// Returns `Error::InvalidState` if already streaming.
+    pub fn start(&mut self, config: &Config<'_>) -> Result<ActualFormat> {
+        self.check_state(State::Idle)?;
+
+        if config.interval.0 == 0 || config.interval.1 == 0 {
+            return Err(Error::BadInterval);
+        }
+
+        if config.negotiation == Negotiation::Strict {
+            if *config.format != self.format.format {
+                return Err(Error::BadFormat);
+            }
+
+            if config.resolution != self.format.resolution {
+                return Err(Error::BadResolution);
+            }
+
+            if config.field != self.format.field {
+                return Err(Error::BadField);
+            }
+        }
+
+        self.rewind()?;
+        self.interval = config.interval;
+        self.started = Instant::now();
+        self.sequence = 0;
+        self.state = State::Streaming;
+
+        Ok(self.format)
+    }
+
+    /// Blocking request of frame, it waits until the frame is due.
+    ///
+    /// Returns `Error::InvalidState` if not streaming.
+    pub fn capture(&mut self) -> Result<Frame> {
+        self.check_state(State::Streaming)?;
+
+        let now = Instant::now();
+
+        if let Some(due) = self.due().filter(|&due| due > now) {
+            thread::sleep(due - now);
+        }
+
+        self.read_frame()
+    }
+
+    /// Non-blocking request of frame.
+    /// Returns `None` if the next frame isn't due yet.
+    ///
+    /// Returns `Error::InvalidState` if not streaming.
+    pub fn try_capture(&mut self) -> Result<Option<Frame>> {
+        self.check_state(State::Streaming)?;
+
+        if self.due().is_some_and(|due| due > Instant::now()) {
+            return Ok(None);
+        }
+
+        self.read_frame().map(Some)
+    }
+
+    /// Stop playing back.
+    ///
+    /// Returns `Error::InvalidState` if not streaming.
+    pub fn stop(&mut self) -> Result<()> {
+        self.check_state(State::Streaming)?;
+        self.state = State::Idle;
+        Ok(())
+    }
+
+    fn check_state(&self, expected: State) -> Result<()> {
+        if self.state != expected {
+            return Err(Error::InvalidState {
+                expected,
+                actual: self.state,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The timestamp of the next frame in microseconds.
+    fn timestamp(&self) -> u64 {
+        let (num, den) = self.interval;
+        let micros = u128::from(self.sequence) * u128::from(num) * 1_000_000 / u128::from(den);
+        micros.min(u64::MAX as u128) as u64
+    }
+
+    /// `None` if the instant is out of range, then the frame is considered due.
+    fn due(&self) -> Option<Instant> {
+        self.started
+            .checked_add(Duration::from_micros(self.timestamp()))
+    }
+
+    fn rewind(&mut self) -> io::Result<()> {
+        match self.footage {
+            Footage::Jpeg { ref mut next, .. } => *next = 0,
+            Footage::Raw { ref mut file } => {
+                file.seek(SeekFrom::Start(0))?;
+            }
+            Footage::Y4m {
+                ref mut file,
+                start,
+            } => {
+                file.seek(SeekFrom::Start(start))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read the next frame, returns `None` at the end of footage.
+    fn read_data(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let size = self.format.sizeimage as usize;
+
+        let read_exact = |file: &mut BufReader<File>| {
+            let mut data = vec![0; size];
+
+            match file.read_exact(&mut data) {
+                Ok(()) => Ok(Some(data)),
+                Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+                Err(err) => Err(err),
+            }
+        };
+
+        match self.footage {
+            Footage::Jpeg {
+                ref files,
+                ref mut next,
+            } => match files.get(*next) {
+                Some(path) => {
+                    *next += 1;
+                    fs::read(path).map(Some)
+                }
+                None => Ok(None),
+            },
+            Footage::Raw { ref mut file } => read_exact(file),
+            Footage::Y4m { ref mut file, .. } => {
+                let mut line = vec![];
+
+                if file.read_until(b'\n', &mut line)? == 0 {
+                    return Ok(None);
+                }
+
+                if !line.starts_with(b"FRAME") {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "bad frame of Y4M",
+                    ));
+                }
+
+                read_exact(file)
+            }
+        }
+    }
+
+    fn read_frame(&mut self) -> Result<Frame> {
+        let data = match self.read_data()? {
+            Some(data) => data,
+            None if self.looping => {
+                self.rewind()?;
+                self.read_data()?.ok_or(Error::EndOfStream)?
+            }
+            None => return Err(Error::EndOfStream),
+        };
+
+        let frame = Frame::new(self.format, data, self.timestamp(), self.sequence as u32);
+        self.sequence = self.sequence.wrapping_add(1);
+        Ok(frame)
+    }
+}
+
+#[test]
+fn test_file_camera() {
+    let dir = std::env::temp_dir().join(format!("rscam-footage-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let config = Config {
+        interval: (1, 1000),
+        resolution: (4, 2),
+        ..Default::default()
+    };
+
+    // Raw YUYV: three frames of 4x2.
+    let raw = dir.join("raw.yuyv");
+    fs::write(&raw, (0..48).map(|i| (i / 16) as u8).collect::<Vec<_>>()).unwrap();
+
+    let mut camera = FileCamera::yuyv(&raw, (4, 2)).unwrap();
+    assert_eq!(camera.start(&config).unwrap().bytesperline, 8);
+
+    for i in 0..3 {
+        let frame = camera.capture().unwrap();
+        assert_eq!(&frame[..], &[i as u8; 16][..]);
+        assert_eq!(frame.sequence(), i);
+        assert_eq!(frame.get_timestamp(), u64::from(i) * 1000);
+    }
+
+    match camera.capture() {
+        Err(Error::EndOfStream) => {}
+        res => panic!("unexpected {:?}", res.map(|_| ())),
+    }
+
+    camera.stop().unwrap();
+    camera.set_looping(true);
+    camera.start(&config).unwrap();
+
+    let frames = (0..4)
+        .map(|_| camera.capture().unwrap()[0])
+        .collect::<Vec<_>>();
+    assert_eq!(frames, vec![0, 1, 2, 0]);
+
+    match FileCamera::yuyv(&raw, (u32::MAX, 2)) {
+        Err(Error::BadResolution) => {}
+        res => panic!("unexpected {:?}", res.map(|_| ())),
+    }
+
+    match y4m_format("W65536 H65536 C420jpeg") {
+        Err(Error::BadResolution) => {}
+        res => panic!("unexpected {:?}", res.map(|_| ())),
+    }
+
+    // Y4M: two frames of 4x2 in 4:2:0.
+    let y4m = dir.join("video.y4m");
+    let mut data = b"YUV4MPEG2 W4 H2 F30:1 Ip A1:1 C420jpeg\n".to_vec();
+
+    for i in 0..2 {
+        data.extend_from_slice(b"FRAME\n");
+        data.extend_from_slice(&[i; 12]);
+    }
+
+    fs::write(&y4m, data).unwrap();
+
+    let mut camera = FileCamera::y4m(&y4m).unwrap();
+    assert_eq!(camera.format().format, *b"YU12");
+
+    match camera.start(&config) {
+        Err(Error::BadFormat) => {}
+        res => panic!("unexpected {:?}", res.map(|_| ())),
+    }
+
+    camera
+        .start(&Config {
+            format: b"YU12",
+            ..config
+        })
+        .unwrap();
+
+    assert_eq!(&camera.capture().unwrap()[..], &[0; 12][..]);
+    assert_eq!(&camera.capture().unwrap()[..], &[1; 12][..]);
+    assert!(camera.capture().is_err());
+
+    // JPEG: only SOF0 is required to get the resolution.
+    let jpegs = dir.join("jpegs");
+    fs::create_dir_all(&jpegs).unwrap();
+
+    let jpeg = [
+        0xff, 0xd8, 0xff, 0xe0, 0x00, 0x04, 0x00, 0x00, 0xff, 0xc0, 0x00, 0x11, 0x08, 0x00, 0xf0,
+        0x01, 0x40, 0x03, 0xff, 0xd9,
+    ];
+
+    fs::write(jpegs.join("b.jpg"), jpeg).unwrap();
+    fs::write(jpegs.join("a.JPEG"), &jpeg[..18]).unwrap();
+    fs::write(jpegs.join("notes.txt"), b"").unwrap();
+
+    let mut camera = FileCamera::jpeg_dir(&jpegs).unwrap();
+    assert_eq!(camera.format().resolution, (320, 240));
+
+    camera
+        .start(&Config {
+            format: b"MJPG",
+            negotiation: Negotiation::Nearest,
+            ..config
+        })
+        .unwrap();
+
+    assert_eq!(camera.capture().unwrap().len(), 18);
+    assert_eq!(camera.capture().unwrap().len(), 20);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
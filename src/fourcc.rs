@@ -0,0 +1,92 @@
+use serde::de::{Deserialize, Deserializer, Error, Unexpected};
+use serde::Serializer;
+
+/// Render FourCC as a string (e.g. `"YUYV"`) instead of an array of numbers.
+/// Non-printable bytes (e.g. the big-endian flag) are escaped as `\xNN`, backslashes as `\\`.
+pub fn serialize<S: Serializer>(format: &[u8; 4], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = String::with_capacity(4);
+
+    for &byte in format {
+        match byte {
+            b'\\' => s.push_str("\\\\"),
+            b' '..=b'~' => s.push(byte as char),
+            _ => s.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+
+    serializer.serialize_str(&s)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 4], D::Error> {
+    let s = String::deserialize(deserializer)?;
+    let expected = &"FourCC of 4 characters or escapes";
+    let hex = |digit: u8| (digit as char).to_digit(16);
+    let mut bytes = vec![];
+    let mut rest = s.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+
+        match *rest {
+            [b'\\', ref tail @ ..] => {
+                bytes.push(b'\\');
+                rest = tail;
+            }
+            [b'x', hi, lo, ref tail @ ..] if hex(hi).is_some() && hex(lo).is_some() => {
+                bytes.push((hex(hi).unwrap() * 16 + hex(lo).unwrap()) as u8);
+                rest = tail;
+            }
+            _ => return Err(D::Error::invalid_value(Unexpected::Str(&s), expected)),
+        }
+    }
+
+    match *bytes {
+        [a, b, c, d] => Ok([a, b, c, d]),
+        _ => Err(D::Error::invalid_length(bytes.len(), expected)),
+    }
+}
+
+/// For borrowed FourCC in `Config`.
+pub fn serialize_ref<S: Serializer>(format: &&[u8; 4], serializer: S) -> Result<S::Ok, S::Error> {
+    serialize(format, serializer)
+}
+
+#[test]
+fn test_fourcc() {
+    use crate::OwnedConfig;
+
+    let config = OwnedConfig {
+        format: *b"MJPG",
+        ..Default::default()
+    };
+
+    let json = serde_json::to_string(&config).unwrap();
+    assert!(json.contains(r#""format":"MJPG""#));
+    assert_eq!(serde_json::from_str::<OwnedConfig>(&json).unwrap(), config);
+
+    let json = json.replace("MJPG", "MJPEG");
+    assert!(serde_json::from_str::<OwnedConfig>(&json).is_err());
+
+    // Big-endian variant of `AR15`.
+    let config = OwnedConfig {
+        format: [b'A', b'R', b'1', b'5' | 0x80],
+        ..Default::default()
+    };
+
+    let json = serde_json::to_string(&config).unwrap();
+    assert!(json.contains(r#""format":"AR1\\xb5""#));
+    assert_eq!(serde_json::from_str::<OwnedConfig>(&json).unwrap(), config);
+
+    let config = OwnedConfig {
+        format: *b"\\\\\0\n",
+        ..Default::default()
+    };
+
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(serde_json::from_str::<OwnedConfig>(&json).unwrap(), config);
+}
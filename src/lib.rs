@@ -16,31 +16,76 @@
 //!
 //! for i in 0..10 {
 //!     let frame = camera.capture().unwrap();
-//!     let mut file = fs::File::create(&format!("frame-{}.jpg", i)).unwrap();
+//!     let mut file = fs::File::create(format!("frame-{}.jpg", i)).unwrap();
 //!     file.write_all(&frame[..]).unwrap();
 //! }
 //! ```
 //!
 //! The wrapper uses v4l2 (e.g. `v4l2_ioctl()` instead of `ioctl()`) until feature `no_wrapper` is
 //! enabled. The feature can be useful when it's desirable to avoid dependence on *libv4l2*.
+//!
+//! Feature `tokio` enables `AsyncCamera`, a stream of frames driven by the tokio reactor.
+//! Feature `mio` implements `mio::event::Source` for `Camera`, register it with
+//! `Interest::PRIORITY` to be woken up by events (see `Camera::subscribe()`).
+//! Feature `serde` implements `Serialize` and `Deserialize` for public data types (e.g. `Camera::modes()`
+//! and `OwnedConfig`), FourCCs are represented as strings.
+//! Feature `mock` enables `MockDevice`, a fake device to test the code using `Camera` without
+//! hardware.
+//!
+//! Sessions with a real device can be written by `Recorder` and replayed later by `Replayer`
+//! on another machine, e.g. to reproduce issues of a specific camera.
+//! `FileCamera` plays back recorded footage (JPEG files, raw YUYV or Y4M) instead of a device,
+//! the code can accept both using `CaptureSource`. Other sources (e.g. test doubles) can implement
+//! the trait too, making frames by `Frame::new()`.
 
 #[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
 compile_error!("rscam (v4l2) is for linux/freebsd only");
 
-use std::convert::From;
+use std::collections::HashMap;
+use std::convert::{From, TryFrom};
 use std::fmt;
+use std::fs;
 use std::io;
 use std::ops::Deref;
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, RawFd};
+use std::path::{Path, PathBuf};
 use std::result;
 use std::slice;
 use std::str;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 pub use self::consts::*;
 pub use self::v4l2::pubconsts as consts;
 use self::v4l2::MappedRegion;
 
+#[cfg(feature = "tokio")]
+pub use self::async_camera::AsyncCamera;
+pub use self::backend::{Backend, Kernel};
+pub use self::events::{Captured, ControlEvent, Event, EventData, EventKind};
+pub use self::file_camera::FileCamera;
+#[cfg(any(test, feature = "mock"))]
+pub use self::mock::{MockDevice, MockFormat, MockGenerator};
+pub use self::modes::{FormatModes, Mode, ModePreferences, ResolutionModes};
+pub use self::names::{control_id, control_name, normalize_name};
+pub use self::profile::{ControlProfile, ControlSetting};
+pub use self::source::CaptureSource;
+pub use self::trace::{Recorder, Replayer};
+
+#[cfg(feature = "tokio")]
+mod async_camera;
+mod backend;
+mod events;
+mod file_camera;
+#[cfg(feature = "serde")]
+mod fourcc;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+mod modes;
+mod names;
+mod profile;
+mod source;
+mod trace;
 mod v4l2;
 
 pub type Result<T> = result::Result<T, Error>;
@@ -49,6 +94,14 @@ pub type Result<T> = result::Result<T, Error>;
 pub enum Error {
     #[error("I/O error: {0}")]
     Io(#[from] io::Error),
+    #[error("{request} failed on {device}: {source}")]
+    Ioctl {
+        /// Symbolic name of the request (e.g. `"VIDIOC_S_FMT"`).
+        request: &'static str,
+        /// Path to the device.
+        device: String,
+        source: io::Error,
+    },
     #[error("invalid or unsupported frame interval")]
     BadInterval,
     #[error("invalid or unsupported resolution (width and/or height)")]
@@ -57,8 +110,51 @@ pub enum Error {
     BadFormat,
     #[error("invalid or unsupported field")]
     BadField,
+    #[error("device doesn't support streaming video capture")]
+    NotCaptureDevice,
+    #[error("invalid state of camera: expected {expected:?}, actual {actual:?}")]
+    InvalidState { expected: State, actual: State },
+    #[error("driver returned unknown buffer #{0}")]
+    BadBuffer(u32),
+    /// Frames captured before `stop()` are still alive, so the driver keeps the buffers.
+    #[error("frames of the previous stream are still borrowed")]
+    FramesBorrowed,
+    #[error("control {id:#x} is rejected: {source}")]
+    ControlRejected { id: u32, source: io::Error },
+    #[error("invalid value {value:?} of control {id:#x}")]
+    BadControlValue { id: u32, value: ControlValue },
+    /// All frames of the footage have been played back, see `FileCamera`.
+    #[error("end of footage")]
+    EndOfStream,
+}
+
+impl Error {
+    fn is_invalid_input(&self) -> bool {
+        match *self {
+            Error::Ioctl { ref source, .. } => source.kind() == io::ErrorKind::InvalidInput,
+            _ => false,
+        }
+    }
+
+    /// The format, the resolution or the interval isn't supported, unlike e.g. `ENODEV`.
+    fn is_unsupported(&self) -> bool {
+        match *self {
+            Error::BadFormat | Error::BadResolution => true,
+            _ => self.is_invalid_input(),
+        }
+    }
 }
 
+/// Turn errors about unsupported modes into `None`, keep other errors.
+fn skip_unsupported<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(ref err) if err.is_unsupported() => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Config<'a> {
     /// The mix of numerator and denominator. v4l2 uses frame intervals instead of frame rates.
     /// Default is `(1, 10)`.
@@ -68,6 +164,7 @@ pub struct Config<'a> {
     pub resolution: (u32, u32),
     /// FourCC of format (e.g. `b"RGB3"`). Note that case matters.
     /// Default is `b"YUYV"`.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "fourcc::serialize_ref"))]
     pub format: &'a [u8; 4],
     /// Storage method of interlaced video. See `FIELD_*` constants.
     /// [Details](http://linuxtv.org/downloads/v4l-dvb-apis/uapi/v4l/field-order.html#v4l2-field).
@@ -76,6 +173,9 @@ pub struct Config<'a> {
     /// Number of buffers in the queue of camera.
     /// Default is `2`.
     pub nbuffers: u32,
+    /// What to do if the driver adjusts the requested parameters.
+    /// Default is `Negotiation::Strict`.
+    pub negotiation: Negotiation,
 }
 
 impl<'a> Default for Config<'a> {
@@ -86,12 +186,205 @@ impl<'a> Default for Config<'a> {
             format: b"YUYV",
             field: FIELD_NONE,
             nbuffers: 2,
+            negotiation: Negotiation::Strict,
+        }
+    }
+}
+
+/// Owned version of `Config`, e.g. to store in files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedConfig {
+    pub interval: (u32, u32),
+    pub resolution: (u32, u32),
+    #[cfg_attr(feature = "serde", serde(with = "fourcc"))]
+    pub format: [u8; 4],
+    pub field: u32,
+    pub nbuffers: u32,
+    pub negotiation: Negotiation,
+}
+
+impl OwnedConfig {
+    pub fn as_config(&self) -> Config<'_> {
+        Config {
+            interval: self.interval,
+            resolution: self.resolution,
+            format: &self.format,
+            field: self.field,
+            nbuffers: self.nbuffers,
+            negotiation: self.negotiation,
+        }
+    }
+}
+
+impl Default for OwnedConfig {
+    fn default() -> OwnedConfig {
+        OwnedConfig::from(&Config::default())
+    }
+}
+
+impl<'a> From<&Config<'a>> for OwnedConfig {
+    fn from(config: &Config<'a>) -> OwnedConfig {
+        OwnedConfig {
+            interval: config.interval,
+            resolution: config.resolution,
+            format: *config.format,
+            field: config.field,
+            nbuffers: config.nbuffers,
+            negotiation: config.negotiation,
+        }
+    }
+}
+
+/// Parameters of the format to check by `Camera::try_format()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FormatRequest {
+    /// Width and height of frame.
+    /// Default is `(640, 480)`.
+    pub resolution: (u32, u32),
+    /// FourCC of format (e.g. `*b"RGB3"`).
+    /// Default is `*b"YUYV"`.
+    #[cfg_attr(feature = "serde", serde(with = "fourcc"))]
+    pub format: [u8; 4],
+    /// Storage method of interlaced video. See `FIELD_*` constants.
+    /// Default is `FIELD_NONE` (progressive).
+    pub field: u32,
+}
+
+impl Default for FormatRequest {
+    fn default() -> FormatRequest {
+        FormatRequest {
+            resolution: (640, 480),
+            format: *b"YUYV",
+            field: FIELD_NONE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Negotiation {
+    /// Fail with `BadResolution`, `BadFormat`, `BadField` or `BadInterval` if the driver
+    /// adjusts the requested parameters.
+    Strict,
+    /// Accept the nearest parameters the driver chooses.
+    Nearest,
+}
+
+/// The format actually chosen by the driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActualFormat {
+    /// Width and height of frame.
+    pub resolution: (u32, u32),
+    /// FourCC of format.
+    #[cfg_attr(feature = "serde", serde(with = "fourcc"))]
+    pub format: [u8; 4],
+    /// Storage method of interlaced video. See `FIELD_*` constants.
+    pub field: u32,
+    /// Distance in bytes between the leftmost pixels in two adjacent lines.
+    pub bytesperline: u32,
+    /// Size in bytes of the buffer to hold a complete frame.
+    pub sizeimage: u32,
+    /// Colorspace of the image, see `enum v4l2_colorspace` in V4L2.
+    pub colorspace: u32,
+}
+
+impl ActualFormat {
+    fn new(fmt: &v4l2::PixFormat) -> ActualFormat {
+        ActualFormat {
+            resolution: (fmt.width, fmt.height),
+            format: FormatInfo::from_fourcc(fmt.pixelformat),
+            field: fmt.field,
+            bytesperline: fmt.bytesperline,
+            sizeimage: fmt.sizeimage,
+            colorspace: fmt.colorspace,
         }
     }
 }
 
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capabilities {
+    /// Name of the driver (e.g. `"uvcvideo"`).
+    pub driver: String,
+    /// Name of the device (e.g. `"HD WebCam"`).
+    pub card: String,
+    /// Location of the device in the system (e.g. `"usb-0000:00:14.0-1"`).
+    pub bus_info: String,
+    /// Version of the driver as `(major, minor, patch)`.
+    pub version: (u32, u32, u32),
+    /// Capabilities of the physical device as a whole. See `CAP_*` constants.
+    pub capabilities: u32,
+    /// Capabilities of the opened device node. See `CAP_*` constants.
+    /// Equals to `capabilities` if the driver doesn't distinguish them.
+    pub device_caps: u32,
+}
+
+impl Capabilities {
+    fn new(cap: &v4l2::Capability) -> Capabilities {
+        let device_caps = if cap.capabilities & CAP_DEVICE_CAPS != 0 {
+            cap.device_caps
+        } else {
+            cap.capabilities
+        };
+
+        Capabilities {
+            driver: buffer_to_string(&cap.driver),
+            card: buffer_to_string(&cap.card),
+            bus_info: buffer_to_string(&cap.bus_info),
+            version: (
+                cap.version >> 16 & 0xff,
+                cap.version >> 8 & 0xff,
+                cap.version & 0xff,
+            ),
+            capabilities: cap.capabilities,
+            device_caps,
+        }
+    }
+
+    /// Whether the node can be used by `Camera::start()` and `Camera::capture()`.
+    pub fn is_capture(&self) -> bool {
+        let required = CAP_VIDEO_CAPTURE | CAP_STREAMING;
+        self.device_caps & required == required
+    }
+
+    /// Whether the node provides metadata (e.g. UVC headers) instead of video.
+    pub fn is_metadata(&self) -> bool {
+        self.device_caps & CAP_META_CAPTURE != 0
+    }
+
+    /// Whether the node is a video output.
+    pub fn is_output(&self) -> bool {
+        self.device_caps & (CAP_VIDEO_OUTPUT | CAP_VIDEO_OUTPUT_MPLANE) != 0
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceInfo {
+    /// Path to the device node (e.g. `/dev/video0`).
+    pub path: PathBuf,
+    /// Links in `/dev/v4l/by-id`, which are stable across reboots and contain serial numbers.
+    pub by_id: Vec<PathBuf>,
+    /// Links in `/dev/v4l/by-path`, which are bound to the physical port (e.g. USB).
+    pub by_path: Vec<PathBuf>,
+    /// Info about the driver and capabilities of the device.
+    pub capabilities: Capabilities,
+}
+
+impl DeviceInfo {
+    /// Open the device.
+    pub fn open(&self) -> io::Result<Camera> {
+        Camera::new(&self.path.to_string_lossy())
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FormatInfo {
     /// FourCC of format (e.g. `b"H264"`).
+    #[cfg_attr(feature = "serde", serde(with = "fourcc"))]
     pub format: [u8; 4],
     /// Information about the format.
     pub description: String,
@@ -104,18 +397,22 @@ pub struct FormatInfo {
 impl FormatInfo {
     fn new(fourcc: u32, desc: &[u8], flags: u32) -> FormatInfo {
         FormatInfo {
-            format: [
-                (fourcc & 0xff) as u8,
-                (fourcc >> 8 & 0xff) as u8,
-                (fourcc >> 16 & 0xff) as u8,
-                (fourcc >> 24 & 0xff) as u8,
-            ],
+            format: FormatInfo::from_fourcc(fourcc),
             description: buffer_to_string(desc),
             compressed: flags & v4l2::FMT_FLAG_COMPRESSED != 0,
             emulated: flags & v4l2::FMT_FLAG_EMULATED != 0,
         }
     }
 
+    fn from_fourcc(fourcc: u32) -> [u8; 4] {
+        [
+            (fourcc & 0xff) as u8,
+            (fourcc >> 8 & 0xff) as u8,
+            (fourcc >> 16 & 0xff) as u8,
+            (fourcc >> 24 & 0xff) as u8,
+        ]
+    }
+
     fn fourcc(fmt: [u8; 4]) -> u32 {
         u32::from(fmt[0])
             | (u32::from(fmt[1])) << 8
@@ -139,6 +436,8 @@ impl fmt::Debug for FormatInfo {
     }
 }
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResolutionInfo {
     Discretes(Vec<(u32, u32)>),
     Stepwise {
@@ -152,10 +451,11 @@ impl fmt::Debug for ResolutionInfo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             ResolutionInfo::Discretes(ref d) => {
-                write!(f, "Discretes: {}x{}", d[0].0, d[0].1)?;
+                f.write_str("Discretes:")?;
 
-                for res in (&d[1..]).iter() {
-                    write!(f, ", {}x{}", res.0, res.1)?;
+                for (i, res) in d.iter().enumerate() {
+                    let sep = if i == 0 { " " } else { ", " };
+                    write!(f, "{}{}x{}", sep, res.0, res.1)?;
                 }
 
                 Ok(())
@@ -169,6 +469,8 @@ impl fmt::Debug for ResolutionInfo {
     }
 }
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntervalInfo {
     Discretes(Vec<(u32, u32)>),
     Stepwise {
@@ -182,10 +484,11 @@ impl fmt::Debug for IntervalInfo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             IntervalInfo::Discretes(ref d) => {
-                write!(f, "Discretes: {}fps", d[0].1 / d[0].0)?;
+                f.write_str("Discretes:")?;
 
-                for res in (&d[1..]).iter() {
-                    write!(f, ", {}fps", res.1 / res.0)?;
+                for (i, res) in d.iter().enumerate() {
+                    let sep = if i == 0 { " " } else { ", " };
+                    write!(f, "{}{}fps", sep, res.1 / res.0)?;
                 }
 
                 Ok(())
@@ -207,18 +510,52 @@ pub struct Frame {
     /// FourCC of the format.
     pub format: [u8; 4],
 
+    actual: ActualFormat,
+    timestamp: u64,
+    sequence: u32,
+    data: Box<dyn AsRef<[u8]> + Send + Sync>,
+}
+
+/// Buffer dequeued from the driver, it's enqueued back on drop.
+struct MappedBuffer {
     region: Arc<MappedRegion>,
     length: u32,
     fd: RawFd,
+    backend: Arc<dyn Backend>,
     buffer: v4l2::Buffer,
 }
 
 impl Frame {
+    /// Make the frame of other sources than devices (see `CaptureSource`).
+    /// The data is dropped together with the frame, so it can return the buffer to a pool.
+    pub fn new<T>(actual: ActualFormat, data: T, timestamp: u64, sequence: u32) -> Frame
+    where
+        T: AsRef<[u8]> + Send + Sync + 'static,
+    {
+        Frame {
+            resolution: actual.resolution,
+            format: actual.format,
+            actual,
+            timestamp,
+            sequence,
+            data: Box::new(data),
+        }
+    }
+
     /// Return frame timestamp in microseconds using monotonically
     /// nondecreasing clock
     pub fn get_timestamp(&self) -> u64 {
-        let t = self.buffer.timestamp;
-        1_000_000 * (t.tv_sec as u64) + (t.tv_usec as u64)
+        self.timestamp
+    }
+
+    /// Return the sequence number of the frame, gaps mean dropped frames.
+    pub fn sequence(&self) -> u32 {
+        self.sequence
+    }
+
+    /// Return the format negotiated with the driver (e.g. to get `bytesperline`).
+    pub fn actual_format(&self) -> &ActualFormat {
+        &self.actual
     }
 }
 
@@ -226,42 +563,79 @@ impl Deref for Frame {
     type Target = [u8];
 
     fn deref(&self) -> &[u8] {
+        (*self.data).as_ref()
+    }
+}
+
+impl AsRef<[u8]> for MappedBuffer {
+    fn as_ref(&self) -> &[u8] {
         unsafe { slice::from_raw_parts(self.region.ptr, self.length as usize) }
     }
 }
 
-impl Drop for Frame {
+impl Drop for MappedBuffer {
     fn drop(&mut self) {
-        let _ = v4l2::xioctl(self.fd, v4l2::VIDIOC_QBUF, &mut self.buffer);
+        let _ = v4l2::xioctl(&*self.backend, self.fd, v4l2::VIDIOC_QBUF, &mut self.buffer);
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum State {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// Not streaming, format can be changed.
     Idle,
+    /// Started, frames can be captured.
     Streaming,
-    Aborted,
 }
 
 pub struct Camera {
     fd: RawFd,
+    backend: Arc<dyn Backend>,
+    device: String,
     state: State,
-    resolution: (u32, u32),
-    format: [u8; 4],
+    format: Option<ActualFormat>,
+    interval: (u32, u32),
     buffers: Vec<Arc<MappedRegion>>,
+    /// The driver refused to free the buffers in `stop()`.
+    buffers_held: bool,
 }
 
 impl Camera {
     pub fn new(device: &str) -> io::Result<Camera> {
+        Camera::with_backend(Arc::new(Kernel), device)
+    }
+
+    /// Open the device using another backend (e.g. `MockDevice`) instead of the kernel.
+    pub fn with_backend(backend: Arc<dyn Backend>, device: &str) -> io::Result<Camera> {
         Ok(Camera {
-            fd: v4l2::open(device)?,
+            fd: backend.open(device)?,
+            backend,
+            device: device.to_string(),
             state: State::Idle,
-            resolution: (0, 0),
-            format: [0; 4],
+            format: None,
+            interval: (0, 0),
             buffers: vec![],
+            buffers_held: false,
         })
     }
 
+    /// Open the device and make sure it's capable of streaming video capture.
+    pub fn open_capture(device: &str) -> Result<Camera> {
+        let camera = Camera::new(device)?;
+
+        if !camera.capabilities()?.is_capture() {
+            return Err(Error::NotCaptureDevice);
+        }
+
+        Ok(camera)
+    }
+
+    /// Get info about the driver and capabilities of the device.
+    pub fn capabilities(&self) -> Result<Capabilities> {
+        let mut cap = v4l2::Capability::new();
+        self.ioctl(v4l2::VIDIOC_QUERYCAP, &mut cap)?;
+        Ok(Capabilities::new(&cap))
+    }
+
     /// Get detailed info about the available formats.
     pub fn formats(&self) -> FormatIter<'_> {
         FormatIter {
@@ -275,9 +649,8 @@ impl Camera {
         let fourcc = FormatInfo::fourcc(format);
         let mut size = v4l2::Frmsizeenum::new(fourcc);
 
-        v4l2::xioctl_valid(self.fd, v4l2::VIDIOC_ENUM_FRAMESIZES, &mut size)?;
-
-        if fourcc != size.pixelformat {
+        if !self.ioctl_valid(v4l2::VIDIOC_ENUM_FRAMESIZES, &mut size)? || fourcc != size.pixelformat
+        {
             return Err(Error::BadFormat);
         }
 
@@ -285,7 +658,7 @@ impl Camera {
             let mut discretes = vec![(size.discrete().width, size.discrete().height)];
             size.index = 1;
 
-            while v4l2::xioctl_valid(self.fd, v4l2::VIDIOC_ENUM_FRAMESIZES, &mut size)? {
+            while self.ioctl_valid(v4l2::VIDIOC_ENUM_FRAMESIZES, &mut size)? {
                 {
                     let discrete = size.discrete();
                     discretes.push((discrete.width, discrete.height));
@@ -310,7 +683,9 @@ impl Camera {
         let fourcc = FormatInfo::fourcc(format);
         let mut ival = v4l2::Frmivalenum::new(fourcc, resolution);
 
-        v4l2::xioctl_valid(self.fd, v4l2::VIDIOC_ENUM_FRAMEINTERVALS, &mut ival)?;
+        if !self.ioctl_valid(v4l2::VIDIOC_ENUM_FRAMEINTERVALS, &mut ival)? {
+            return Err(Error::BadResolution);
+        }
 
         if fourcc != ival.pixelformat {
             return Err(Error::BadFormat);
@@ -324,7 +699,7 @@ impl Camera {
             let mut discretes = vec![(ival.discrete().numerator, ival.discrete().denominator)];
             ival.index = 1;
 
-            while v4l2::xioctl_valid(self.fd, v4l2::VIDIOC_ENUM_FRAMEINTERVALS, &mut ival)? {
+            while self.ioctl_valid(v4l2::VIDIOC_ENUM_FRAMEINTERVALS, &mut ival)? {
                 {
                     let discrete = ival.discrete();
                     discretes.push((discrete.numerator, discrete.denominator));
@@ -344,6 +719,97 @@ impl Camera {
         }
     }
 
+    /// Find the mode which suits the preferences best among all available ones.
+    /// Returns `None` if no mode is acceptable (e.g. all are slower than `min_fps`).
+    /// Formats and resolutions, which the driver refuses to enumerate, are skipped.
+    ///
+    /// ```no_run
+    /// use rscam::{Camera, ModePreferences};
+    ///
+    /// let mut camera = Camera::new("/dev/video0").unwrap();
+    ///
+    /// let mode = camera
+    ///     .select_mode(&ModePreferences {
+    ///         formats: vec![*b"MJPG", *b"YUYV"],
+    ///         resolution: Some((1280, 720)),
+    ///         min_fps: 25,
+    ///         ..Default::default()
+    ///     })
+    ///     .unwrap()
+    ///     .expect("no suitable mode");
+    ///
+    /// camera.start(&mode.config()).unwrap();
+    /// ```
+    pub fn select_mode(&self, prefs: &ModePreferences) -> Result<Option<Mode>> {
+        let mut best = None;
+
+        for format in self.formats() {
+            let format = format?;
+
+            // Some drivers list formats, which can't be enumerated further (e.g. emulated ones).
+            let resolutions = match skip_unsupported(self.resolutions(format.format))? {
+                Some(resolutions) => resolutions,
+                None => continue,
+            };
+
+            for resolution in modes::candidate_resolutions(&resolutions, prefs.resolution) {
+                let intervals = match skip_unsupported(self.intervals(format.format, resolution))? {
+                    Some(intervals) => intervals,
+                    None => continue,
+                };
+
+                let interval = match modes::fastest_interval(&intervals) {
+                    Some(interval) if prefs.accepts(interval) => interval,
+                    _ => continue,
+                };
+
+                let score = prefs.score(&format, resolution, interval);
+                let mode = Mode {
+                    format: format.format,
+                    resolution,
+                    interval,
+                };
+
+                match best {
+                    Some((ref best_score, _)) if *best_score <= score => {}
+                    _ => best = Some((score, mode)),
+                }
+            }
+        }
+
+        Ok(best.map(|(_, mode)| mode))
+    }
+
+    /// Get the complete table of formats, resolutions and intervals.
+    ///
+    /// Stepwise resolutions are expanded to the minimum, the maximum and common resolutions
+    /// (e.g. `1280x720`) in the range, the full range is kept in `FormatModes::sizes`.
+    /// Resolutions and intervals, which the driver refuses to enumerate, are `None`.
+    pub fn modes(&self) -> Result<Vec<FormatModes>> {
+        let mut modes = vec![];
+
+        for format in self.formats() {
+            let format = format?;
+            let sizes = skip_unsupported(self.resolutions(format.format))?;
+            let mut resolutions = vec![];
+
+            for resolution in sizes.iter().flat_map(modes::expand_resolutions) {
+                resolutions.push(ResolutionModes {
+                    resolution,
+                    intervals: skip_unsupported(self.intervals(format.format, resolution))?,
+                });
+            }
+
+            modes.push(FormatModes {
+                format,
+                sizes,
+                resolutions,
+            });
+        }
+
+        Ok(modes)
+    }
+
     /// Get info about all controls.
     pub fn controls(&self) -> ControlIter<'_> {
         ControlIter {
@@ -357,23 +823,31 @@ impl Camera {
     pub fn controls_by_class(&self, class: u32) -> ControlIter<'_> {
         ControlIter {
             camera: self,
-            id: class as u32,
+            id: class,
             class,
         }
     }
 
     /// Get info about the control by id.
-    pub fn get_control(&self, id: u32) -> io::Result<Control> {
-        let mut qctrl = v4l2::QueryCtrl::new(id);
-        v4l2::xioctl(self.fd, v4l2::VIDIOC_QUERYCTRL, &mut qctrl)?;
+    pub fn get_control(&self, id: u32) -> Result<Control> {
+        self.control(&self.query_control(id)?)
+    }
+
+    /// Get the value and menu items of the queried control.
+    fn control(&self, qctrl: &v4l2::QueryExtCtrl) -> Result<Control> {
+        let nr_of_dims = qctrl.nr_of_dims.min(4) as usize;
 
         let data = match qctrl.qtype {
+            // Arrays of scalars aren't supported.
+            v4l2::CTRL_TYPE_INTEGER..=v4l2::CTRL_TYPE_INTEGER_MENU if nr_of_dims > 0 => {
+                CtrlData::Unknown
+            }
             v4l2::CTRL_TYPE_INTEGER => CtrlData::Integer {
                 value: self.get_control_value(qctrl.id)?,
-                default: qctrl.default_value,
-                minimum: qctrl.minimum,
-                maximum: qctrl.maximum,
-                step: qctrl.step,
+                default: qctrl.default_value as i32,
+                minimum: qctrl.minimum as i32,
+                maximum: qctrl.maximum as i32,
+                step: qctrl.step as i32,
             },
             v4l2::CTRL_TYPE_BOOLEAN => CtrlData::Boolean {
                 value: self.get_control_value(qctrl.id)? != 0,
@@ -385,19 +859,13 @@ impl Camera {
                 items: self.get_menu_items(qctrl.id, qctrl.minimum as u32, qctrl.maximum as u32)?,
             },
             v4l2::CTRL_TYPE_BUTTON => CtrlData::Button,
-            v4l2::CTRL_TYPE_INTEGER64 => {
-                let mut qectrl = v4l2::QueryExtCtrl::new(qctrl.id);
-
-                v4l2::xioctl(self.fd, v4l2::VIDIOC_QUERY_EXT_CTRL, &mut qectrl)?;
-
-                CtrlData::Integer64 {
-                    value: self.get_ext_control_value(qctrl.id)?,
-                    default: qectrl.default_value,
-                    minimum: qectrl.minimum,
-                    maximum: qectrl.maximum,
-                    step: qectrl.step as i64,
-                }
-            }
+            v4l2::CTRL_TYPE_INTEGER64 => CtrlData::Integer64 {
+                value: self.get_ext_control_value(qctrl.id)?,
+                default: qctrl.default_value,
+                minimum: qctrl.minimum,
+                maximum: qctrl.maximum,
+                step: qctrl.step as i64,
+            },
             v4l2::CTRL_TYPE_CTRL_CLASS => CtrlData::CtrlClass,
             v4l2::CTRL_TYPE_STRING => CtrlData::String {
                 value: self.get_string_control(qctrl.id, qctrl.maximum as u32)?,
@@ -419,6 +887,35 @@ impl Camera {
                     qctrl.maximum as u32,
                 )?,
             },
+            v4l2::CTRL_TYPE_U8 => CtrlData::U8Array {
+                value: self.get_payload(qctrl.id, qctrl.elem_size * qctrl.elems)?,
+                default: qctrl.default_value as u8,
+                minimum: qctrl.minimum as u8,
+                maximum: qctrl.maximum as u8,
+                step: qctrl.step as u8,
+            },
+            v4l2::CTRL_TYPE_U16 => CtrlData::U16Array {
+                value: u16_elements(&self.get_payload(qctrl.id, qctrl.elem_size * qctrl.elems)?),
+                default: qctrl.default_value as u16,
+                minimum: qctrl.minimum as u16,
+                maximum: qctrl.maximum as u16,
+                step: qctrl.step as u16,
+            },
+            v4l2::CTRL_TYPE_U32 => CtrlData::U32Array {
+                value: u32_elements(&self.get_payload(qctrl.id, qctrl.elem_size * qctrl.elems)?),
+                default: qctrl.default_value as u32,
+                minimum: qctrl.minimum as u32,
+                maximum: qctrl.maximum as u32,
+                step: qctrl.step as u32,
+            },
+            v4l2::CTRL_TYPE_AREA if nr_of_dims == 0 => {
+                // `struct v4l2_area` of width and height.
+                let area = u32_elements(&self.get_payload(qctrl.id, 8)?);
+
+                CtrlData::Area {
+                    value: (area[0], area[1]),
+                }
+            }
             _ => CtrlData::Unknown,
         };
 
@@ -427,32 +924,100 @@ impl Camera {
             name: buffer_to_string(&qctrl.name),
             data,
             flags: qctrl.flags,
+            elem_size: qctrl.elem_size,
+            dims: qctrl.dims[..nr_of_dims].to_vec(),
         })
     }
 
-    fn get_control_value(&self, id: u32) -> io::Result<i32> {
+    /// Find the control by the canonical name of the standard control (see `control_name()`)
+    /// or by the name reported by the driver, which is compared after `normalize_name()`.
+    /// Returns `None` if there is no such control.
+    ///
+    /// ```no_run
+    /// use rscam::Camera;
+    ///
+    /// let camera = Camera::new("/dev/video0").unwrap();
+    /// let ctrl = camera.control_by_name("exposure_time_absolute").unwrap();
+    /// ```
+    pub fn control_by_name(&self, name: &str) -> Result<Option<Control>> {
+        if let Some(id) = control_id(name) {
+            match self.get_control(id) {
+                Ok(ctrl) => return Ok(Some(ctrl)),
+                Err(ref err) if err.is_invalid_input() => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let name = normalize_name(name);
+
+        for ctrl in self.controls() {
+            let ctrl = ctrl?;
+
+            if normalize_name(&ctrl.name) == name {
+                return Ok(Some(ctrl));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Query the control, the extended query is emulated if the driver doesn't support it.
+    fn query_control(&self, id: u32) -> Result<v4l2::QueryExtCtrl> {
+        let mut qectrl = v4l2::QueryExtCtrl::new(id);
+
+        match v4l2::xioctl(
+            &*self.backend,
+            self.fd,
+            v4l2::VIDIOC_QUERY_EXT_CTRL,
+            &mut qectrl,
+        ) {
+            Ok(()) => return Ok(qectrl),
+            Err(ref err) if err.raw_os_error() == Some(libc::ENOTTY) => {}
+            Err(err) => return Err(self.ioctl_error(v4l2::VIDIOC_QUERY_EXT_CTRL, err)),
+        }
+
+        let mut qctrl = v4l2::QueryCtrl::new(id & !v4l2::NEXT_COMPOUND);
+        self.ioctl(v4l2::VIDIOC_QUERYCTRL, &mut qctrl)?;
+
+        qectrl.id = qctrl.id;
+        qectrl.qtype = qctrl.qtype;
+        qectrl.name = qctrl.name;
+        qectrl.minimum = i64::from(qctrl.minimum);
+        qectrl.maximum = i64::from(qctrl.maximum);
+        qectrl.step = qctrl.step as u64;
+        qectrl.default_value = i64::from(qctrl.default_value);
+        qectrl.flags = qctrl.flags;
+
+        if qctrl.qtype == v4l2::CTRL_TYPE_STRING {
+            qectrl.elem_size = qctrl.maximum as u32 + 1;
+        }
+
+        Ok(qectrl)
+    }
+
+    fn get_control_value(&self, id: u32) -> Result<i32> {
         let mut ctrl = v4l2::Control::new(id);
-        v4l2::xioctl(self.fd, v4l2::VIDIOC_G_CTRL, &mut ctrl)?;
+        self.ioctl(v4l2::VIDIOC_G_CTRL, &mut ctrl)?;
         Ok(ctrl.value)
     }
 
-    fn get_ext_control_value(&self, id: u32) -> io::Result<i64> {
+    fn get_ext_control_value(&self, id: u32) -> Result<i64> {
         let mut ctrl = v4l2::ExtControl::new(id, 0);
         {
-            let mut ctrls = v4l2::ExtControls::new(id & v4l2::ID2CLASS, &mut ctrl);
-            v4l2::xioctl(self.fd, v4l2::VIDIOC_G_EXT_CTRLS, &mut ctrls)?;
+            let mut ctrls = v4l2::ExtControls::new(id & v4l2::ID2CLASS, slice::from_mut(&mut ctrl));
+            self.ioctl(v4l2::VIDIOC_G_EXT_CTRLS, &mut ctrls)?;
         }
         Ok(ctrl.value)
     }
 
-    fn get_menu_items(&self, id: u32, min: u32, max: u32) -> io::Result<Vec<CtrlMenuItem>> {
+    fn get_menu_items(&self, id: u32, min: u32, max: u32) -> Result<Vec<CtrlMenuItem>> {
         let mut items = vec![];
         let mut qmenu = v4l2::QueryMenu::new(id);
 
         for index in min..=max {
-            qmenu.index = index as u32;
+            qmenu.index = index;
 
-            if v4l2::xioctl_valid(self.fd, v4l2::VIDIOC_QUERYMENU, &mut qmenu)? {
+            if self.ioctl_valid(v4l2::VIDIOC_QUERYMENU, &mut qmenu)? {
                 items.push(CtrlMenuItem {
                     index,
                     name: buffer_to_string(qmenu.data.name()),
@@ -463,14 +1028,14 @@ impl Camera {
         Ok(items)
     }
 
-    fn get_int_menu_items(&self, id: u32, min: u32, max: u32) -> io::Result<Vec<CtrlIntMenuItem>> {
+    fn get_int_menu_items(&self, id: u32, min: u32, max: u32) -> Result<Vec<CtrlIntMenuItem>> {
         let mut items = vec![];
         let mut qmenu = v4l2::QueryMenu::new(id);
 
         for index in min..=max {
-            qmenu.index = index as u32;
+            qmenu.index = index;
 
-            if v4l2::xioctl_valid(self.fd, v4l2::VIDIOC_QUERYMENU, &mut qmenu)? {
+            if self.ioctl_valid(v4l2::VIDIOC_QUERYMENU, &mut qmenu)? {
                 items.push(CtrlIntMenuItem {
                     index,
                     value: qmenu.data.value(),
@@ -481,97 +1046,510 @@ impl Camera {
         Ok(items)
     }
 
-    fn get_string_control(&self, id: u32, size: u32) -> io::Result<String> {
-        let mut buffer = Vec::with_capacity(size as usize + 1);
-        let mut ctrl = v4l2::ExtControl::new(id, size + 1);
+    fn get_string_control(&self, id: u32, size: u32) -> Result<String> {
+        Ok(buffer_to_string(&self.get_payload(id, size + 1)?))
+    }
+
+    fn get_payload(&self, id: u32, size: u32) -> Result<Vec<u8>> {
+        let mut buffer = vec![0; size as usize];
+        let mut ctrl = v4l2::ExtControl::new(id, size);
         ctrl.value = buffer.as_mut_ptr() as i64;
-        let mut ctrls = v4l2::ExtControls::new(id & v4l2::ID2CLASS, &mut ctrl);
-        v4l2::xioctl(self.fd, v4l2::VIDIOC_G_EXT_CTRLS, &mut ctrls)?;
-        unsafe { buffer.set_len(size as usize + 1) };
-        Ok(buffer_to_string(&buffer[..]))
+        let mut ctrls = v4l2::ExtControls::new(id & v4l2::ID2CLASS, slice::from_mut(&mut ctrl));
+        self.ioctl(v4l2::VIDIOC_G_EXT_CTRLS, &mut ctrls)?;
+        Ok(buffer)
     }
 
-    /// Set value of the control.
-    pub fn set_control<T: Settable>(&self, id: u32, value: &T) -> io::Result<()> {
-        let mut ctrl = v4l2::ExtControl::new(id, 0);
-        ctrl.value = value.unify();
-        let mut ctrls = v4l2::ExtControls::new(id & v4l2::ID2CLASS, &mut ctrl);
-        v4l2::xioctl(self.fd, v4l2::VIDIOC_S_EXT_CTRLS, &mut ctrls)?;
+    /// Set value of the control. The value is marshalled according to the type of the control,
+    /// so numeric variants of `ControlValue` are interchangeable.
+    ///
+    /// ```no_run
+    /// use rscam::{Camera, CID_BRIGHTNESS};
+    ///
+    /// let camera = Camera::new("/dev/video0").unwrap();
+    /// camera.set_control(CID_BRIGHTNESS, 5).unwrap();
+    /// ```
+    pub fn set_control<T: Into<ControlValue>>(&self, id: u32, value: T) -> Result<()> {
+        self.ext_controls(v4l2::VIDIOC_S_EXT_CTRLS, &[(id, value.into())])?;
         Ok(())
     }
 
-    /// Start streaming.
+    /// Set values of several controls at once. All values are validated by the driver before
+    /// applying, but if the hardware fails to apply one of them, the preceding controls may be
+    /// already changed.
     ///
-    /// # Panics
-    /// If recalled or called after `stop()`.
-    pub fn start(&mut self, config: &Config<'_>) -> Result<()> {
-        assert_eq!(self.state, State::Idle);
+    /// Returns `Error::ControlRejected` if the driver rejects one of the controls.
+    pub fn set_controls(&self, controls: &[(u32, ControlValue)]) -> Result<()> {
+        self.ext_controls(v4l2::VIDIOC_S_EXT_CTRLS, controls)?;
+        Ok(())
+    }
 
-        self.tune_format(config.resolution, *config.format, config.field)?;
-        self.tune_stream(config.interval)?;
-        self.alloc_buffers(config.nbuffers)?;
+    /// Check values of controls without applying them.
+    /// Returns values as they would be set by the driver.
+    ///
+    /// Returns `Error::BadControlValue` if the value doesn't match the type, the range,
+    /// the step or menu items of the control, see `CtrlData::accepts()`.
+    pub fn try_controls(
+        &self,
+        controls: &[(u32, ControlValue)],
+    ) -> Result<Vec<(u32, ControlValue)>> {
+        let mut queries = vec![];
+
+        for &(id, ref value) in controls {
+            let qctrl = self.query_control(id)?;
+
+            if !self.control(&qctrl)?.data.accepts(value) {
+                return Err(Error::BadControlValue {
+                    id,
+                    value: value.clone(),
+                });
+            }
 
-        if let Err(err) = self.streamon() {
+            queries.push(qctrl);
+        }
+
+        let values = self.ext_queried_controls(v4l2::VIDIOC_TRY_EXT_CTRLS, controls, &queries)?;
+        Ok(controls.iter().map(|c| c.0).zip(values).collect())
+    }
+
+    fn ext_controls(
+        &self,
+        request: usize,
+        controls: &[(u32, ControlValue)],
+    ) -> Result<Vec<ControlValue>> {
+        let mut queries = vec![];
+
+        for &(id, _) in controls {
+            queries.push(self.query_control(id)?);
+        }
+
+        self.ext_queried_controls(request, controls, &queries)
+    }
+
+    fn ext_queried_controls(
+        &self,
+        request: usize,
+        controls: &[(u32, ControlValue)],
+        queries: &[v4l2::QueryExtCtrl],
+    ) -> Result<Vec<ControlValue>> {
+        if controls.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Payloads are passed by pointers, which must be alive during the call.
+        let mut payloads = vec![];
+
+        for (&(id, ref value), qctrl) in controls.iter().zip(queries) {
+            payloads.push(ControlValue::marshal(value, qctrl).ok_or_else(|| {
+                Error::BadControlValue {
+                    id,
+                    value: value.clone(),
+                }
+            })?);
+        }
+
+        let mut ctrls = controls
+            .iter()
+            .zip(payloads.iter_mut())
+            .map(|(&(id, ref value), payload)| match *payload {
+                Some(ref mut payload) => {
+                    let mut ctrl = v4l2::ExtControl::new(id, payload.len() as u32);
+                    ctrl.value = payload.as_mut_ptr() as i64;
+                    ctrl
+                }
+                None => {
+                    let mut ctrl = v4l2::ExtControl::new(id, 0);
+                    ctrl.value = value.unify();
+                    ctrl
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Old kernels require the common class, new ones accept any controls.
+        let class = controls[0].0 & v4l2::ID2CLASS;
+        let class = if controls.iter().all(|c| c.0 & v4l2::ID2CLASS == class) {
+            class
+        } else {
+            v4l2::CTRL_WHICH_CUR_VAL
+        };
+
+        let mut ext = v4l2::ExtControls::new(class, &mut ctrls);
+
+        if let Err(source) = v4l2::xioctl(&*self.backend, self.fd, request, &mut ext) {
+            let mut error_idx = ext.error_idx as usize;
+
+            // If validation fails, nothing is applied and the kernel sets `error_idx` to
+            // `count`, but trying the same controls points out the wrong one.
+            if error_idx == controls.len() && request == v4l2::VIDIOC_S_EXT_CTRLS {
+                let mut ext = v4l2::ExtControls::new(class, &mut ctrls);
+                let request = v4l2::VIDIOC_TRY_EXT_CTRLS;
+
+                if v4l2::xioctl(&*self.backend, self.fd, request, &mut ext).is_err() {
+                    error_idx = ext.error_idx as usize;
+                }
+            }
+
+            return Err(match controls.get(error_idx) {
+                Some(&(id, _)) => Error::ControlRejected { id, source },
+                None => self.ioctl_error(request, source),
+            });
+        }
+
+        // The driver could adjust values.
+        Ok(queries
+            .iter()
+            .zip(ctrls.iter().zip(payloads))
+            .map(|(qctrl, (ctrl, payload))| ControlValue::unmarshal(qctrl, ctrl.value, payload))
+            .collect())
+    }
+
+    /// Get values of all writable and active controls to apply them later by `apply_profile()`.
+    pub fn snapshot_controls(&self) -> Result<ControlProfile> {
+        let mut controls = vec![];
+
+        for ctrl in self.controls() {
+            if let Some(setting) = ControlSetting::new(&ctrl?) {
+                controls.push(setting);
+            }
+        }
+
+        Ok(ControlProfile { controls })
+    }
+
+    /// Set values of controls from the profile. Controls switching automatic modes
+    /// (e.g. `CID_EXPOSURE_AUTO`) are set first, then the rest in order of the profile.
+    pub fn apply_profile(&self, profile: &ControlProfile) -> Result<()> {
+        let mut first = vec![];
+        let mut rest = vec![];
+
+        for setting in &profile.controls {
+            let qctrl = self.query_control(setting.id)?;
+
+            if profile::is_applied_first(setting.id, qctrl.flags) {
+                first.push(setting);
+            } else {
+                rest.push(setting);
+            }
+        }
+
+        for setting in first.into_iter().chain(rest) {
+            self.set_control(setting.id, setting.value.clone())?;
+        }
+
+        Ok(())
+    }
+
+    /// Start streaming. Can be called again after `stop()`, but all frames captured before
+    /// have to be dropped, otherwise the driver refuses to change the format.
+    ///
+    /// Returns the format chosen by the driver, which can differ from the requested one only
+    /// if `Negotiation::Nearest` is used.
+    ///
+    /// Returns `Error::InvalidState` if already streaming and `Error::FramesBorrowed`
+    /// if frames captured before `stop()` are still alive.
+    pub fn start(&mut self, config: &Config<'_>) -> Result<ActualFormat> {
+        self.check_state(State::Idle)?;
+        self.release_buffers()?;
+
+        let strict = config.negotiation == Negotiation::Strict;
+        let format = self.tune_format(config.resolution, *config.format, config.field, strict)?;
+        let interval = self.tune_stream(config.interval, strict)?;
+
+        if let Err(err) = self.alloc_buffers(config.nbuffers) {
             self.free_buffers();
-            return Err(Error::Io(err));
+            return Err(err);
         }
 
-        self.resolution = config.resolution;
-        self.format = [
-            config.format[0],
-            config.format[1],
-            config.format[2],
-            config.format[3],
-        ];
+        if let Err(err) = self.streamon() {
+            self.free_buffers();
+            return Err(err);
+        }
 
+        self.format = Some(format);
+        self.interval = interval;
         self.state = State::Streaming;
 
-        Ok(())
+        Ok(format)
+    }
+
+    /// Ask the driver which format it would choose for the request.
+    /// Nothing is changed on the device.
+    pub fn try_format(&self, request: &FormatRequest) -> Result<ActualFormat> {
+        let fourcc = FormatInfo::fourcc(request.format);
+        let mut fmt = v4l2::Format::new(request.resolution, fourcc, request.field);
+
+        self.ioctl(v4l2::VIDIOC_TRY_FMT, &mut fmt)?;
+
+        Ok(ActualFormat::new(&fmt.fmt))
+    }
+
+    /// Get the format currently configured on the device (possibly by another application).
+    pub fn current_format(&self) -> Result<ActualFormat> {
+        let mut fmt = v4l2::Format::new((0, 0), 0, 0);
+
+        self.ioctl(v4l2::VIDIOC_G_FMT, &mut fmt)?;
+
+        Ok(ActualFormat::new(&fmt.fmt))
+    }
+
+    /// Get the format chosen by the driver in the last call of `start()`.
+    pub fn format(&self) -> Option<ActualFormat> {
+        self.format
+    }
+
+    /// Get the frame interval chosen by the driver in the last call of `start()`.
+    pub fn interval(&self) -> Option<(u32, u32)> {
+        self.format.map(|_| self.interval)
     }
 
     /// Blocking request of frame.
     /// It dequeues buffer from a driver, which will be enqueueed after destructing `Frame`.
     ///
-    /// # Panics
-    /// If called w/o streaming.
-    pub fn capture(&self) -> io::Result<Frame> {
-        assert_eq!(self.state, State::Streaming);
+    /// Returns `Error::InvalidState` if not streaming.
+    pub fn capture(&self) -> Result<Frame> {
+        loop {
+            if let Some(frame) = self.try_capture()? {
+                return Ok(frame);
+            }
+
+            self.backend.poll(self.fd, v4l2::POLLIN, None)?;
+        }
+    }
+
+    /// Request of frame, which waits at most `timeout`.
+    /// Returns `None` if no frame has been captured in time.
+    ///
+    /// Returns `Error::InvalidState` if not streaming.
+    pub fn capture_timeout(&self, timeout: Duration) -> Result<Option<Frame>> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Some(frame) = self.try_capture()? {
+                return Ok(Some(frame));
+            }
+
+            let now = Instant::now();
+
+            if now >= deadline
+                || !self
+                    .backend
+                    .poll(self.fd, v4l2::POLLIN, Some(deadline - now))?
+            {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Non-blocking request of frame.
+    /// Returns `None` if there is no captured frame yet.
+    ///
+    /// Returns `Error::InvalidState` if not streaming.
+    pub fn try_capture(&self) -> Result<Option<Frame>> {
+        self.check_state(State::Streaming)?;
 
         let mut buf = v4l2::Buffer::new();
 
-        v4l2::xioctl(self.fd, v4l2::VIDIOC_DQBUF, &mut buf)?;
-        assert!(buf.index < self.buffers.len() as u32);
+        match v4l2::xioctl(&*self.backend, self.fd, v4l2::VIDIOC_DQBUF, &mut buf) {
+            Ok(()) => {}
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+            Err(err) => return Err(self.ioctl_error(v4l2::VIDIOC_DQBUF, err)),
+        }
 
-        Ok(Frame {
-            resolution: self.resolution,
-            format: self.format,
+        if buf.index >= self.buffers.len() as u32 {
+            return Err(Error::BadBuffer(buf.index));
+        }
+
+        let actual = self.format.expect("format is set while streaming");
+
+        let t = buf.timestamp;
+        let timestamp = 1_000_000 * (t.tv_sec as u64) + (t.tv_usec as u64);
+        let sequence = buf.sequence;
+
+        let mapped = MappedBuffer {
             region: self.buffers[buf.index as usize].clone(),
             length: buf.bytesused,
             fd: self.fd,
+            backend: self.backend.clone(),
             buffer: buf,
-        })
+        };
+
+        Ok(Some(Frame::new(actual, mapped, timestamp, sequence)))
+    }
+
+    /// Subscribe to events of the kind, they can be dequeued by `dequeue_event()` then.
+    /// The device becomes readable with priority (`POLLPRI`) while an event is pending.
+    pub fn subscribe(&self, kind: EventKind) -> Result<()> {
+        self.ioctl(v4l2::VIDIOC_SUBSCRIBE_EVENT, &mut kind.subscription())
+    }
+
+    /// Stop receiving events of the kind.
+    pub fn unsubscribe(&self, kind: EventKind) -> Result<()> {
+        self.ioctl(v4l2::VIDIOC_UNSUBSCRIBE_EVENT, &mut kind.subscription())
+    }
+
+    /// Non-blocking request of event.
+    /// Returns `None` if there is no pending event.
+    pub fn dequeue_event(&self) -> Result<Option<Event>> {
+        let mut event = v4l2::Event::new();
+
+        match v4l2::xioctl(&*self.backend, self.fd, v4l2::VIDIOC_DQEVENT, &mut event) {
+            Ok(()) => Ok(Some(Event::new(&event))),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(self.ioctl_error(v4l2::VIDIOC_DQEVENT, err)),
+        }
+    }
+
+    /// Request of event, which waits at most `timeout` or forever if it's `None`.
+    /// Returns `None` if no event has been received in time.
+    pub fn wait_event(&self, timeout: Option<Duration>) -> Result<Option<Event>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            if let Some(event) = self.dequeue_event()? {
+                return Ok(Some(event));
+            }
+
+            let timeout = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+
+                    if now >= deadline {
+                        return Ok(None);
+                    }
+
+                    Some(deadline - now)
+                }
+                None => None,
+            };
+
+            if !self.backend.poll(self.fd, v4l2::POLLPRI, timeout)? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Blocking request of frame or event of subscribed kinds, pending events go before frames.
+    ///
+    /// Returns `Error::InvalidState` if not streaming.
+    ///
+    /// ```no_run
+    /// use rscam::{Camera, Captured, Config, EventData, EventKind};
+    ///
+    /// let mut camera = Camera::new("/dev/video0").unwrap();
+    /// camera.subscribe(EventKind::SourceChange(0)).unwrap();
+    /// camera.start(&Config::default()).unwrap();
+    ///
+    /// loop {
+    ///     match camera.capture_or_event().unwrap() {
+    ///         Captured::Frame(frame) => println!("Frame of length {}", frame.len()),
+    ///         Captured::Event(event) => match event.data {
+    ///             EventData::SourceChange { .. } => {
+    ///                 camera.reconfigure(&Config::default()).unwrap();
+    ///             }
+    ///             EventData::Eos => break,
+    ///             _ => {}
+    ///         },
+    ///     }
+    /// }
+    /// ```
+    pub fn capture_or_event(&self) -> Result<Captured> {
+        loop {
+            if let Some(captured) = self.try_capture_or_event()? {
+                return Ok(captured);
+            }
+
+            self.backend
+                .poll(self.fd, v4l2::POLLIN | v4l2::POLLPRI, None)?;
+        }
+    }
+
+    /// Non-blocking request of frame or event of subscribed kinds.
+    /// Returns `None` if there is neither pending event nor captured frame.
+    ///
+    /// Returns `Error::InvalidState` if not streaming.
+    pub fn try_capture_or_event(&self) -> Result<Option<Captured>> {
+        self.check_state(State::Streaming)?;
+
+        if let Some(event) = self.dequeue_event()? {
+            return Ok(Some(Captured::Event(event)));
+        }
+
+        Ok(self.try_capture()?.map(Captured::Frame))
     }
 
     /// Stop streaming. Otherwise it's called after destructing `Camera`.
+    /// Streaming can be started again with another config.
     ///
-    /// # Panics
-    /// If called w/o streaming.
-    pub fn stop(&mut self) -> io::Result<()> {
-        assert_eq!(self.state, State::Streaming);
+    /// Returns `Error::InvalidState` if not streaming.
+    pub fn stop(&mut self) -> Result<()> {
+        self.check_state(State::Streaming)?;
 
         self.streamoff()?;
         self.free_buffers();
 
-        self.state = State::Aborted;
+        self.state = State::Idle;
 
         Ok(())
     }
 
-    fn tune_format(&self, resolution: (u32, u32), format: [u8; 4], field: u32) -> Result<()> {
+    /// Restart streaming with another config (e.g. resolution) without reopening the device.
+    /// All frames captured before have to be dropped.
+    ///
+    /// Returns `Error::InvalidState` if not streaming.
+    pub fn reconfigure(&mut self, config: &Config<'_>) -> Result<ActualFormat> {
+        self.check_state(State::Streaming)?;
+        self.stop()?;
+        self.start(config)
+    }
+
+    /// Whether `start()` has been called and streaming isn't stopped.
+    pub fn is_streaming(&self) -> bool {
+        self.state == State::Streaming
+    }
+
+    fn ioctl<T>(&self, request: usize, arg: &mut T) -> Result<()> {
+        v4l2::xioctl(&*self.backend, self.fd, request, arg)
+            .map_err(|err| self.ioctl_error(request, err))
+    }
+
+    fn ioctl_valid<T>(&self, request: usize, arg: &mut T) -> Result<bool> {
+        v4l2::xioctl_valid(&*self.backend, self.fd, request, arg)
+            .map_err(|err| self.ioctl_error(request, err))
+    }
+
+    fn ioctl_error(&self, request: usize, source: io::Error) -> Error {
+        Error::Ioctl {
+            request: v4l2::request_name(request),
+            device: self.device.clone(),
+            source,
+        }
+    }
+
+    fn check_state(&self, expected: State) -> Result<()> {
+        if self.state == expected {
+            Ok(())
+        } else {
+            Err(Error::InvalidState {
+                expected,
+                actual: self.state,
+            })
+        }
+    }
+
+    fn tune_format(
+        &self,
+        resolution: (u32, u32),
+        format: [u8; 4],
+        field: u32,
+        strict: bool,
+    ) -> Result<ActualFormat> {
         let fourcc = FormatInfo::fourcc(format);
-        let mut fmt = v4l2::Format::new(resolution, fourcc, field as u32);
+        let mut fmt = v4l2::Format::new(resolution, fourcc, field);
 
-        v4l2::xioctl(self.fd, v4l2::VIDIOC_S_FMT, &mut fmt)?;
+        self.ioctl(v4l2::VIDIOC_S_FMT, &mut fmt)?;
+
+        if !strict {
+            return Ok(ActualFormat::new(&fmt.fmt));
+        }
 
         if resolution != (fmt.fmt.width, fmt.fmt.height) {
             return Err(Error::BadResolution);
@@ -581,37 +1559,43 @@ impl Camera {
             return Err(Error::BadFormat);
         }
 
-        if field as u32 != fmt.fmt.field {
+        if field != fmt.fmt.field {
             return Err(Error::BadField);
         }
 
-        Ok(())
+        Ok(ActualFormat::new(&fmt.fmt))
     }
 
-    fn tune_stream(&self, interval: (u32, u32)) -> Result<()> {
+    fn tune_stream(&self, interval: (u32, u32), strict: bool) -> Result<(u32, u32)> {
         let mut parm = v4l2::StreamParm::new(interval);
 
-        v4l2::xioctl(self.fd, v4l2::VIDIOC_S_PARM, &mut parm)?;
+        self.ioctl(v4l2::VIDIOC_S_PARM, &mut parm)?;
         let time = parm.parm.timeperframe;
+        let actual = (time.numerator, time.denominator);
+
+        if !strict {
+            return Ok(actual);
+        }
 
         match (time.numerator * interval.1, time.denominator * interval.0) {
             (0, _) | (_, 0) => Err(Error::BadInterval),
             (x, y) if x != y => Err(Error::BadInterval),
-            _ => Ok(()),
+            _ => Ok(actual),
         }
     }
 
     fn alloc_buffers(&mut self, nbuffers: u32) -> Result<()> {
         let mut req = v4l2::RequestBuffers::new(nbuffers);
 
-        v4l2::xioctl(self.fd, v4l2::VIDIOC_REQBUFS, &mut req)?;
+        self.ioctl(v4l2::VIDIOC_REQBUFS, &mut req)?;
 
-        for i in 0..nbuffers {
+        for i in 0..req.count {
             let mut buf = v4l2::Buffer::new();
             buf.index = i;
-            v4l2::xioctl(self.fd, v4l2::VIDIOC_QUERYBUF, &mut buf)?;
+            self.ioctl(v4l2::VIDIOC_QUERYBUF, &mut buf)?;
 
-            let region = v4l2::mmap(buf.length as usize, self.fd, buf.m)?;
+            let region =
+                MappedRegion::new(self.backend.clone(), self.fd, buf.length as usize, buf.m)?;
             self.buffers.push(Arc::new(region));
         }
 
@@ -620,25 +1604,49 @@ impl Camera {
 
     fn free_buffers(&mut self) {
         self.buffers.clear();
+
+        // The driver refuses if some frames are still alive, it's retried by the next `start()`.
+        let mut req = v4l2::RequestBuffers::new(0);
+        self.buffers_held = self.ioctl(v4l2::VIDIOC_REQBUFS, &mut req).is_err();
+    }
+
+    /// Free the buffers kept by the driver after `stop()`.
+    fn release_buffers(&mut self) -> Result<()> {
+        if !self.buffers_held {
+            return Ok(());
+        }
+
+        let mut req = v4l2::RequestBuffers::new(0);
+
+        match v4l2::xioctl(&*self.backend, self.fd, v4l2::VIDIOC_REQBUFS, &mut req) {
+            Ok(()) => {}
+            Err(ref err) if err.raw_os_error() == Some(libc::EBUSY) => {
+                return Err(Error::FramesBorrowed)
+            }
+            Err(err) => return Err(self.ioctl_error(v4l2::VIDIOC_REQBUFS, err)),
+        }
+
+        self.buffers_held = false;
+        Ok(())
     }
 
-    fn streamon(&self) -> io::Result<()> {
+    fn streamon(&self) -> Result<()> {
         for i in 0..self.buffers.len() {
             let mut buf = v4l2::Buffer::new();
             buf.index = i as u32;
 
-            v4l2::xioctl(self.fd, v4l2::VIDIOC_QBUF, &mut buf)?;
+            self.ioctl(v4l2::VIDIOC_QBUF, &mut buf)?;
         }
 
         let mut typ = v4l2::BUF_TYPE_VIDEO_CAPTURE;
-        v4l2::xioctl(self.fd, v4l2::VIDIOC_STREAMON, &mut typ)?;
+        self.ioctl(v4l2::VIDIOC_STREAMON, &mut typ)?;
 
         Ok(())
     }
 
-    fn streamoff(&mut self) -> io::Result<()> {
+    fn streamoff(&mut self) -> Result<()> {
         let mut typ = v4l2::BUF_TYPE_VIDEO_CAPTURE;
-        v4l2::xioctl(self.fd, v4l2::VIDIOC_STREAMOFF, &mut typ)?;
+        self.ioctl(v4l2::VIDIOC_STREAMOFF, &mut typ)?;
 
         Ok(())
     }
@@ -646,11 +1654,69 @@ impl Camera {
 
 impl Drop for Camera {
     fn drop(&mut self) {
-        if self.state == State::Streaming {
+        if self.is_streaming() {
             let _ = self.stop();
         }
 
-        let _ = v4l2::close(self.fd);
+        let _ = self.backend.close(self.fd);
+    }
+}
+
+impl AsRawFd for Camera {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl AsFd for Camera {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw(self.fd) }
+    }
+}
+
+impl FromRawFd for Camera {
+    /// Take ownership of the descriptor of the already opened device (e.g. received from
+    /// a privileged process). The descriptor is switched to non-blocking mode.
+    unsafe fn from_raw_fd(fd: RawFd) -> Camera {
+        let device = fs::read_link(format!("/proc/self/fd/{}", fd))
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| format!("fd {}", fd));
+
+        Camera {
+            fd: v4l2::fd_open(fd),
+            backend: Arc::new(Kernel),
+            device,
+            state: State::Idle,
+            format: None,
+            interval: (0, 0),
+            buffers: vec![],
+            buffers_held: false,
+        }
+    }
+}
+
+#[cfg(feature = "mio")]
+impl mio::event::Source for Camera {
+    fn register(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        mio::unix::SourceFd(&self.fd).register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &mio::Registry,
+        token: mio::Token,
+        interests: mio::Interest,
+    ) -> io::Result<()> {
+        mio::unix::SourceFd(&self.fd).reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &mio::Registry) -> io::Result<()> {
+        mio::unix::SourceFd(&self.fd).deregister(registry)
     }
 }
 
@@ -660,13 +1726,13 @@ pub struct FormatIter<'a> {
 }
 
 impl<'a> Iterator for FormatIter<'a> {
-    type Item = io::Result<FormatInfo>;
+    type Item = Result<FormatInfo>;
 
-    fn next(&mut self) -> Option<io::Result<FormatInfo>> {
+    fn next(&mut self) -> Option<Result<FormatInfo>> {
         let mut fmt = v4l2::FmtDesc::new();
         fmt.index = self.index;
 
-        match v4l2::xioctl_valid(self.camera.fd, v4l2::VIDIOC_ENUM_FMT, &mut fmt) {
+        match self.camera.ioctl_valid(v4l2::VIDIOC_ENUM_FMT, &mut fmt) {
             Ok(true) => {
                 self.index += 1;
                 Some(Ok(FormatInfo::new(
@@ -688,12 +1754,15 @@ pub struct ControlIter<'a> {
 }
 
 impl<'a> Iterator for ControlIter<'a> {
-    type Item = io::Result<Control>;
+    type Item = Result<Control>;
 
-    fn next(&mut self) -> Option<io::Result<Control>> {
-        match self.camera.get_control(self.id | v4l2::NEXT_CTRL) {
-            Ok(ref ctrl) if self.class > 0 && ctrl.id & v4l2::ID2CLASS != self.class as u32 => None,
-            Err(ref err) if err.kind() == io::ErrorKind::InvalidInput => None,
+    fn next(&mut self) -> Option<Result<Control>> {
+        match self
+            .camera
+            .get_control(self.id | v4l2::NEXT_CTRL | v4l2::NEXT_COMPOUND)
+        {
+            Ok(ref ctrl) if self.class > 0 && ctrl.id & v4l2::ID2CLASS != self.class => None,
+            Err(ref err) if err.is_invalid_input() => None,
             Ok(ctrl) => {
                 self.id = ctrl.id;
                 Some(Ok(ctrl))
@@ -703,56 +1772,22 @@ impl<'a> Iterator for ControlIter<'a> {
     }
 }
 
-pub trait Settable {
-    fn unify(&self) -> i64;
-}
-
-impl Settable for i64 {
-    fn unify(&self) -> i64 {
-        *self
-    }
-}
-
-impl Settable for i32 {
-    fn unify(&self) -> i64 {
-        i64::from(*self)
-    }
-}
-
-impl Settable for u32 {
-    fn unify(&self) -> i64 {
-        i64::from(*self)
-    }
-}
-
-impl Settable for bool {
-    fn unify(&self) -> i64 {
-        *self as i64
-    }
-}
-
-impl<'a> Settable for &'a str {
-    fn unify(&self) -> i64 {
-        self.as_ptr() as i64
-    }
-}
-
-impl Settable for String {
-    fn unify(&self) -> i64 {
-        self.as_ptr() as i64
-    }
-}
-
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Control {
     pub id: u32,
     pub name: String,
     pub data: CtrlData,
     /// See `FLAG_*` constants for details.
     pub flags: u32,
+    /// Size in bytes of a single element of the value.
+    pub elem_size: u32,
+    /// Dimensions of arrays (e.g. `[rows, columns]` of a grid), empty for single values.
+    pub dims: Vec<u32>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CtrlData {
     Integer {
         value: i32,
@@ -795,21 +1830,374 @@ pub enum CtrlData {
         default: u32,
         items: Vec<CtrlIntMenuItem>,
     },
+    /// Elements in row-major order, see `Control::dims`.
+    U8Array {
+        value: Vec<u8>,
+        default: u8,
+        minimum: u8,
+        maximum: u8,
+        step: u8,
+    },
+    /// Elements in row-major order, see `Control::dims`.
+    U16Array {
+        value: Vec<u16>,
+        default: u16,
+        minimum: u16,
+        maximum: u16,
+        step: u16,
+    },
+    /// Elements in row-major order, see `Control::dims`.
+    U32Array {
+        value: Vec<u32>,
+        default: u32,
+        minimum: u32,
+        maximum: u32,
+        step: u32,
+    },
+    /// Width and height.
+    Area {
+        value: (u32, u32),
+    },
     Unknown,
 }
 
+impl CtrlData {
+    /// Whether the value is valid for the control: the type, the range, the step and menu items
+    /// are checked. Numeric variants of `ControlValue` are interchangeable.
+    pub fn accepts(&self, value: &ControlValue) -> bool {
+        if let Some(elements) = value.elements() {
+            let fits_all = |count: usize, minimum: u32, maximum: u32, step: u32| {
+                elements.len() == count
+                    && elements
+                        .iter()
+                        .all(|&e| modes::fits(e, minimum, maximum, step))
+            };
+
+            return match *self {
+                CtrlData::U8Array {
+                    ref value,
+                    minimum,
+                    maximum,
+                    step,
+                    ..
+                } => fits_all(
+                    value.len(),
+                    u32::from(minimum),
+                    u32::from(maximum),
+                    u32::from(step),
+                ),
+                CtrlData::U16Array {
+                    ref value,
+                    minimum,
+                    maximum,
+                    step,
+                    ..
+                } => fits_all(
+                    value.len(),
+                    u32::from(minimum),
+                    u32::from(maximum),
+                    u32::from(step),
+                ),
+                CtrlData::U32Array {
+                    ref value,
+                    minimum,
+                    maximum,
+                    step,
+                    ..
+                } => fits_all(value.len(), minimum, maximum, step),
+                CtrlData::Area { .. } => elements.len() == 2,
+                _ => false,
+            };
+        }
+
+        let number = match *value {
+            ControlValue::String(ref s) => {
+                return match *self {
+                    CtrlData::String {
+                        minimum,
+                        maximum,
+                        step,
+                        ..
+                    } => modes::fits(s.len() as i64, minimum.into(), maximum.into(), step.into()),
+                    _ => false,
+                }
+            }
+            ControlValue::Button => return matches!(*self, CtrlData::Button),
+            // Payloads are validated by the driver.
+            ControlValue::Compound(_) => {
+                return matches!(
+                    *self,
+                    CtrlData::U8Array { .. }
+                        | CtrlData::U16Array { .. }
+                        | CtrlData::U32Array { .. }
+                        | CtrlData::Area { .. }
+                        | CtrlData::Unknown
+                )
+            }
+            ref value => value.unify(),
+        };
+
+        match *self {
+            CtrlData::Integer {
+                minimum,
+                maximum,
+                step,
+                ..
+            } => modes::fits(number, minimum.into(), maximum.into(), step.into()),
+            CtrlData::Boolean { .. } => number == 0 || number == 1,
+            CtrlData::Menu { ref items, .. } => items.iter().any(|i| i64::from(i.index) == number),
+            CtrlData::IntegerMenu { ref items, .. } => {
+                items.iter().any(|i| i64::from(i.index) == number)
+            }
+            CtrlData::Integer64 {
+                minimum,
+                maximum,
+                step,
+                ..
+            } => modes::fits(number, minimum, maximum, step),
+            CtrlData::Bitmask { maximum, .. } => {
+                0 <= number && number <= i64::from(u32::MAX) && number as u32 & !maximum == 0
+            }
+            CtrlData::Button | CtrlData::Unknown => true,
+            CtrlData::CtrlClass
+            | CtrlData::String { .. }
+            | CtrlData::U8Array { .. }
+            | CtrlData::U16Array { .. }
+            | CtrlData::U32Array { .. }
+            | CtrlData::Area { .. } => false,
+        }
+    }
+
+    /// Get the current value if the control has one.
+    pub fn value(&self) -> Option<ControlValue> {
+        Some(match *self {
+            CtrlData::Integer { value, .. } => ControlValue::Integer(value),
+            CtrlData::Boolean { value, .. } => ControlValue::Boolean(value),
+            CtrlData::Menu { value, .. } => ControlValue::Menu(value),
+            CtrlData::Integer64 { value, .. } => ControlValue::Integer64(value),
+            CtrlData::String { ref value, .. } => ControlValue::String(value.clone()),
+            CtrlData::Bitmask { value, .. } => ControlValue::Bitmask(value),
+            CtrlData::IntegerMenu { value, .. } => ControlValue::Menu(value),
+            CtrlData::U8Array { ref value, .. } => ControlValue::U8Array(value.clone()),
+            CtrlData::U16Array { ref value, .. } => ControlValue::U16Array(value.clone()),
+            CtrlData::U32Array { ref value, .. } => ControlValue::U32Array(value.clone()),
+            CtrlData::Area { value } => ControlValue::Area(value),
+            CtrlData::Button | CtrlData::CtrlClass | CtrlData::Unknown => return None,
+        })
+    }
+}
+
+/// Value of the control.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ControlValue {
+    Integer(i32),
+    Integer64(i64),
+    Boolean(bool),
+    Menu(u32),
+    Bitmask(u32),
+    String(String),
+    /// Elements of the array in row-major order.
+    U8Array(Vec<u8>),
+    U16Array(Vec<u16>),
+    U32Array(Vec<u32>),
+    /// Width and height.
+    Area((u32, u32)),
+    /// Press of the button, it has no value.
+    Button,
+    /// Raw payload of the compound control (see `FLAG_HAS_PAYLOAD`) in the native byte order.
+    Compound(Vec<u8>),
+}
+
+impl ControlValue {
+    /// Numeric value as passed to the driver, payloads are passed separately.
+    fn unify(&self) -> i64 {
+        match *self {
+            ControlValue::Integer(value) => i64::from(value),
+            ControlValue::Integer64(value) => value,
+            ControlValue::Boolean(value) => value as i64,
+            ControlValue::Menu(value) | ControlValue::Bitmask(value) => i64::from(value),
+            ControlValue::String(_)
+            | ControlValue::U8Array(_)
+            | ControlValue::U16Array(_)
+            | ControlValue::U32Array(_)
+            | ControlValue::Area(_)
+            | ControlValue::Button
+            | ControlValue::Compound(_) => 0,
+        }
+    }
+
+    /// Elements of arrays and areas, which are marshalled according to the type of the control.
+    fn elements(&self) -> Option<Vec<u32>> {
+        Some(match *self {
+            ControlValue::U8Array(ref value) => value.iter().map(|&e| u32::from(e)).collect(),
+            ControlValue::U16Array(ref value) => value.iter().map(|&e| u32::from(e)).collect(),
+            ControlValue::U32Array(ref value) => value.clone(),
+            ControlValue::Area((width, height)) => vec![width, height],
+            _ => return None,
+        })
+    }
+
+    /// Make the payload for the control of the queried type: `Some(None)` for numeric values,
+    /// `Some(Some(bytes))` for strings and compounds, `None` if the value doesn't fit the type
+    /// or the number of elements.
+    fn marshal(&self, qctrl: &v4l2::QueryExtCtrl) -> Option<Option<Vec<u8>>> {
+        let is_string = qctrl.qtype == v4l2::CTRL_TYPE_STRING;
+        let has_payload = qctrl.flags & FLAG_HAS_PAYLOAD != 0 || is_string;
+        let size = qctrl.elem_size as usize * qctrl.elems as usize;
+
+        match *self {
+            ControlValue::String(ref s) if is_string => {
+                // The driver expects the buffer for the longest string and the terminating NUL.
+                let mut payload = s.bytes().chain(Some(0)).collect::<Vec<_>>();
+                payload.resize(payload.len().max(qctrl.elem_size as usize), 0);
+                Some(Some(payload))
+            }
+            ControlValue::Compound(ref payload) if has_payload && !is_string => {
+                Some(Some(payload.clone())).filter(|_| payload.len() == size)
+            }
+            ControlValue::String(_) | ControlValue::Compound(_) => None,
+            _ if is_string => None,
+            _ if has_payload => self
+                .marshal_elements(qctrl)
+                .filter(|payload| payload.len() == size)
+                .map(Some),
+            ControlValue::U8Array(_)
+            | ControlValue::U16Array(_)
+            | ControlValue::U32Array(_)
+            | ControlValue::Area(_) => None,
+            ControlValue::Button if qctrl.qtype != v4l2::CTRL_TYPE_BUTTON => None,
+            _ => Some(None),
+        }
+    }
+
+    fn marshal_elements(&self, qctrl: &v4l2::QueryExtCtrl) -> Option<Vec<u8>> {
+        let elements = self.elements()?;
+        let mut payload = vec![];
+
+        match qctrl.qtype {
+            v4l2::CTRL_TYPE_U8 => {
+                for e in elements {
+                    payload.push(u8::try_from(e).ok()?);
+                }
+            }
+            v4l2::CTRL_TYPE_U16 => {
+                for e in elements {
+                    payload.extend_from_slice(&u16::try_from(e).ok()?.to_ne_bytes());
+                }
+            }
+            v4l2::CTRL_TYPE_U32 => {
+                for e in elements {
+                    payload.extend_from_slice(&e.to_ne_bytes());
+                }
+            }
+            v4l2::CTRL_TYPE_AREA if elements.len() == 2 => {
+                for e in elements {
+                    payload.extend_from_slice(&e.to_ne_bytes());
+                }
+            }
+            _ => return None,
+        }
+
+        Some(payload)
+    }
+
+    /// Make the value of the queried type from the raw value or the payload.
+    fn unmarshal(qctrl: &v4l2::QueryExtCtrl, raw: i64, payload: Option<Vec<u8>>) -> ControlValue {
+        match (qctrl.qtype, payload) {
+            (v4l2::CTRL_TYPE_STRING, Some(payload)) => {
+                ControlValue::String(buffer_to_string(&payload))
+            }
+            (v4l2::CTRL_TYPE_U8, Some(payload)) => ControlValue::U8Array(payload),
+            (v4l2::CTRL_TYPE_U16, Some(payload)) => ControlValue::U16Array(u16_elements(&payload)),
+            (v4l2::CTRL_TYPE_U32, Some(payload)) => ControlValue::U32Array(u32_elements(&payload)),
+            (v4l2::CTRL_TYPE_AREA, Some(ref payload)) if payload.len() >= 8 => {
+                let area = u32_elements(payload);
+                ControlValue::Area((area[0], area[1]))
+            }
+            (_, Some(payload)) => ControlValue::Compound(payload),
+            (v4l2::CTRL_TYPE_INTEGER64, None) => ControlValue::Integer64(raw),
+            (v4l2::CTRL_TYPE_BOOLEAN, None) => ControlValue::Boolean(raw as i32 != 0),
+            (v4l2::CTRL_TYPE_MENU, None) | (v4l2::CTRL_TYPE_INTEGER_MENU, None) => {
+                ControlValue::Menu(raw as u32)
+            }
+            (v4l2::CTRL_TYPE_BITMASK, None) => ControlValue::Bitmask(raw as u32),
+            (v4l2::CTRL_TYPE_BUTTON, None) => ControlValue::Button,
+            (_, None) => ControlValue::Integer(raw as i32),
+        }
+    }
+}
+
+impl From<i32> for ControlValue {
+    fn from(value: i32) -> ControlValue {
+        ControlValue::Integer(value)
+    }
+}
+
+impl From<i64> for ControlValue {
+    fn from(value: i64) -> ControlValue {
+        ControlValue::Integer64(value)
+    }
+}
+
+impl From<u32> for ControlValue {
+    fn from(value: u32) -> ControlValue {
+        ControlValue::Menu(value)
+    }
+}
+
+impl From<bool> for ControlValue {
+    fn from(value: bool) -> ControlValue {
+        ControlValue::Boolean(value)
+    }
+}
+
+impl From<&str> for ControlValue {
+    fn from(value: &str) -> ControlValue {
+        ControlValue::String(value.to_string())
+    }
+}
+
+impl From<String> for ControlValue {
+    fn from(value: String) -> ControlValue {
+        ControlValue::String(value)
+    }
+}
+
+impl From<Vec<u8>> for ControlValue {
+    fn from(value: Vec<u8>) -> ControlValue {
+        ControlValue::Compound(value)
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CtrlMenuItem {
     pub index: u32,
     pub name: String,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CtrlIntMenuItem {
     pub index: u32,
     pub value: i64,
 }
 
+fn u16_elements(payload: &[u8]) -> Vec<u16> {
+    payload
+        .chunks_exact(2)
+        .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+        .collect()
+}
+
+fn u32_elements(payload: &[u8]) -> Vec<u32> {
+    payload
+        .chunks_exact(4)
+        .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
 fn buffer_to_string(buf: &[u8]) -> String {
     // Instead of unstable `position_elem()`.
     String::from_utf8_lossy(match buf.iter().position(|&c| c == 0) {
@@ -819,7 +2207,222 @@ fn buffer_to_string(buf: &[u8]) -> String {
     .into_owned()
 }
 
+fn collect_links(dir: &str, links: &mut HashMap<PathBuf, Vec<PathBuf>>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        if let Ok(target) = fs::canonicalize(entry.path()) {
+            links.entry(target).or_default().push(entry.path());
+        }
+    }
+}
+
+fn video_index(path: &Path) -> Option<u32> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix("video")?
+        .parse()
+        .ok()
+}
+
+/// Get info about all V4L2 devices (`/dev/video*`) in the system.
+///
+/// Devices which can't be opened (e.g. due to permissions) are skipped.
+pub fn devices() -> io::Result<Vec<DeviceInfo>> {
+    let mut by_id = HashMap::new();
+    let mut by_path = HashMap::new();
+    collect_links("/dev/v4l/by-id", &mut by_id);
+    collect_links("/dev/v4l/by-path", &mut by_path);
+
+    let mut nodes = fs::read_dir("/dev")?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter_map(|p| video_index(&p).map(|i| (i, p)))
+        .collect::<Vec<_>>();
+
+    nodes.sort();
+
+    let mut devices = vec![];
+
+    for (_, path) in nodes {
+        let capabilities = match Camera::new(&path.to_string_lossy()) {
+            Ok(camera) => match camera.capabilities() {
+                Ok(capabilities) => capabilities,
+                Err(_) => continue,
+            },
+            Err(_) => continue,
+        };
+
+        let target = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+
+        devices.push(DeviceInfo {
+            by_id: by_id.remove(&target).unwrap_or_default(),
+            by_path: by_path.remove(&target).unwrap_or_default(),
+            path,
+            capabilities,
+        });
+    }
+
+    Ok(devices)
+}
+
 /// Alias for `Camera::new()`.
 pub fn new(device: &str) -> io::Result<Camera> {
     Camera::new(device)
 }
+
+#[test]
+fn test_accepts() {
+    let integer = CtrlData::Integer {
+        value: 0,
+        default: 0,
+        minimum: -10,
+        maximum: 10,
+        step: 5,
+    };
+
+    assert!(integer.accepts(&ControlValue::Integer(5)));
+    assert!(integer.accepts(&ControlValue::Integer64(-10)));
+    assert!(!integer.accepts(&ControlValue::Integer(3)));
+    assert!(!integer.accepts(&ControlValue::Integer(15)));
+    assert!(!integer.accepts(&ControlValue::String("5".into())));
+
+    let menu = CtrlData::Menu {
+        value: 0,
+        default: 0,
+        items: vec![
+            CtrlMenuItem {
+                index: 0,
+                name: "Disabled".into(),
+            },
+            CtrlMenuItem {
+                index: 2,
+                name: "60 Hz".into(),
+            },
+        ],
+    };
+
+    assert!(menu.accepts(&ControlValue::Menu(2)));
+    assert!(menu.accepts(&ControlValue::Integer(0)));
+    assert!(!menu.accepts(&ControlValue::Menu(1)));
+
+    let bitmask = CtrlData::Bitmask {
+        value: 0,
+        default: 0,
+        maximum: 0b1010,
+    };
+
+    assert!(bitmask.accepts(&ControlValue::Bitmask(0b1000)));
+    assert!(!bitmask.accepts(&ControlValue::Bitmask(0b0100)));
+
+    let string = CtrlData::String {
+        value: String::new(),
+        minimum: 2,
+        maximum: 4,
+        step: 1,
+    };
+
+    assert!(string.accepts(&ControlValue::String("abc".into())));
+    assert!(!string.accepts(&ControlValue::String("abcde".into())));
+    assert!(!string.accepts(&ControlValue::Integer(3)));
+}
+
+#[test]
+fn test_marshal() {
+    let mut qctrl = v4l2::QueryExtCtrl::new(CID_BRIGHTNESS);
+    qctrl.qtype = v4l2::CTRL_TYPE_INTEGER;
+
+    assert_eq!(ControlValue::Menu(5).marshal(&qctrl), Some(None));
+    assert_eq!(ControlValue::String("5".into()).marshal(&qctrl), None);
+    assert_eq!(ControlValue::Button.marshal(&qctrl), None);
+    assert_eq!(
+        ControlValue::unmarshal(&qctrl, -5, None),
+        ControlValue::Integer(-5)
+    );
+
+    qctrl.qtype = v4l2::CTRL_TYPE_STRING;
+    qctrl.elem_size = 6;
+    qctrl.flags = FLAG_HAS_PAYLOAD;
+
+    let payload = ControlValue::String("abc".into()).marshal(&qctrl);
+    assert_eq!(payload, Some(Some(b"abc\0\0\0".to_vec())));
+    assert_eq!(
+        ControlValue::unmarshal(&qctrl, 0, payload.unwrap()),
+        ControlValue::String("abc".into())
+    );
+    assert_eq!(ControlValue::Integer(1).marshal(&qctrl), None);
+
+    qctrl.qtype = v4l2::CTRL_TYPE_BUTTON;
+    qctrl.flags = FLAG_WRITE_ONLY;
+
+    assert_eq!(ControlValue::Button.marshal(&qctrl), Some(None));
+    assert_eq!(ControlValue::Compound(vec![1]).marshal(&qctrl), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_control_value_serde() {
+    let values = vec![
+        ControlValue::Integer(1),
+        ControlValue::Menu(1),
+        ControlValue::U8Array(vec![1, 2]),
+        ControlValue::Compound(vec![1, 2]),
+        ControlValue::Button,
+    ];
+
+    let json = serde_json::to_string(&values).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Vec<ControlValue>>(&json).unwrap(),
+        values
+    );
+}
+
+#[test]
+fn test_arrays() {
+    let grid = CtrlData::U16Array {
+        value: vec![0; 4],
+        default: 0,
+        minimum: 0,
+        maximum: 1000,
+        step: 1,
+    };
+
+    assert!(grid.accepts(&ControlValue::U16Array(vec![1, 2, 3, 1000])));
+    assert!(grid.accepts(&ControlValue::U8Array(vec![1, 2, 3, 4])));
+    assert!(!grid.accepts(&ControlValue::U16Array(vec![1, 2, 3])));
+    assert!(!grid.accepts(&ControlValue::U32Array(vec![1, 2, 3, 1001])));
+    assert!(!grid.accepts(&ControlValue::Integer(1)));
+
+    let mut qctrl = v4l2::QueryExtCtrl::new(CID_DETECT_MD_THRESHOLD_GRID);
+    qctrl.qtype = v4l2::CTRL_TYPE_U16;
+    qctrl.flags = FLAG_HAS_PAYLOAD;
+    qctrl.elem_size = 2;
+    qctrl.elems = 2;
+
+    let payload = ControlValue::U8Array(vec![1, 2]).marshal(&qctrl);
+    assert_eq!(
+        payload,
+        Some(Some([1u16.to_ne_bytes(), 2u16.to_ne_bytes()].concat()))
+    );
+    assert_eq!(
+        ControlValue::unmarshal(&qctrl, 0, payload.unwrap()),
+        ControlValue::U16Array(vec![1, 2])
+    );
+    assert_eq!(ControlValue::U32Array(vec![70000, 1]).marshal(&qctrl), None);
+    assert_eq!(ControlValue::U16Array(vec![1, 2, 3]).marshal(&qctrl), None);
+    assert_eq!(ControlValue::Compound(vec![1, 2, 3]).marshal(&qctrl), None);
+    assert_eq!(ControlValue::Integer(1).marshal(&qctrl), None);
+
+    qctrl.qtype = v4l2::CTRL_TYPE_AREA;
+    qctrl.elem_size = 8;
+    qctrl.elems = 1;
+
+    let payload = ControlValue::Area((640, 480)).marshal(&qctrl).unwrap();
+    assert_eq!(
+        ControlValue::unmarshal(&qctrl, 0, payload),
+        ControlValue::Area((640, 480))
+    );
+}
@@ -0,0 +1,1545 @@
+use std::collections::VecDeque;
+use std::io;
+use std::os::raw::c_void;
+use std::os::unix::io::RawFd;
+use std::ptr;
+use std::slice;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use libc::{pollfd, EACCES, EAGAIN, EBADF, EBUSY, EINVAL, ENOENT, ENOSPC, ENOTTY, ERANGE};
+
+use crate::consts::*;
+use crate::{modes, v4l2};
+use crate::{ActualFormat, Backend, Camera, Control, ControlValue, CtrlData, CtrlMenuItem};
+use crate::{FormatInfo, IntervalInfo, ResolutionInfo};
+
+/// Generator of frames: fills the buffer for the format and the sequence number of the frame,
+/// returns the number of used bytes.
+pub type MockGenerator = dyn Fn(&ActualFormat, u32, &mut [u8]) -> usize + Send + Sync;
+
+/// Fake device to run `Camera` without hardware (e.g. in tests). Frames are generated
+/// immediately after they are requested, timestamps are derived from the interval.
+/// Descriptors are eventfds, so the camera can be polled and registered in reactors as usual.
+/// Only control events are supported, they are signaled to `Camera` methods by another eventfd.
+///
+/// ```
+/// use rscam::{Config, MockDevice};
+///
+/// let mut camera = MockDevice::default().open().unwrap();
+///
+/// camera.start(&Config {
+///     interval: (1, 30),
+///     ..Default::default()
+/// }).unwrap();
+///
+/// let frame = camera.capture().unwrap();
+/// assert_eq!(frame.resolution, (640, 480));
+/// ```
+#[derive(Clone)]
+pub struct MockDevice {
+    /// Name of the device, see `Capabilities::card`.
+    /// Default is `"Mock camera"`.
+    pub name: String,
+    /// Default is `YUYV` in `640x480` and `1280x720` and `MJPG` in stepwise resolutions.
+    pub formats: Vec<MockFormat>,
+    /// Controls with their initial values, `elem_size` and `dims` are derived from the data.
+    /// Default is brightness, automatic white balance and power line frequency.
+    pub controls: Vec<Control>,
+    /// Default fills the whole buffer with the sequence number.
+    pub generator: Arc<MockGenerator>,
+}
+
+/// Format of `MockDevice`.
+#[derive(Debug, Clone)]
+pub struct MockFormat {
+    /// FourCC of format (e.g. `b"YUYV"`).
+    pub format: [u8; 4],
+    pub description: String,
+    pub compressed: bool,
+    pub resolutions: ResolutionInfo,
+    /// Intervals for all resolutions.
+    pub intervals: IntervalInfo,
+}
+
+impl Default for MockDevice {
+    fn default() -> MockDevice {
+        MockDevice {
+            name: "Mock camera".into(),
+            formats: vec![
+                MockFormat {
+                    format: *b"YUYV",
+                    description: "YUYV 4:2:2".into(),
+                    compressed: false,
+                    resolutions: ResolutionInfo::Discretes(vec![(640, 480), (1280, 720)]),
+                    intervals: IntervalInfo::Discretes(vec![(1, 30), (1, 15)]),
+                },
+                MockFormat {
+                    format: *b"MJPG",
+                    description: "Motion-JPEG".into(),
+                    compressed: true,
+                    resolutions: ResolutionInfo::Stepwise {
+                        min: (160, 120),
+                        max: (1920, 1080),
+                        step: (16, 8),
+                    },
+                    intervals: IntervalInfo::Discretes(vec![(1, 30)]),
+                },
+            ],
+            controls: vec![
+                Control {
+                    id: CID_BRIGHTNESS,
+                    name: "Brightness".into(),
+                    data: CtrlData::Integer {
+                        value: 128,
+                        default: 128,
+                        minimum: 0,
+                        maximum: 255,
+                        step: 1,
+                    },
+                    flags: FLAG_SLIDER,
+                    elem_size: 4,
+                    dims: vec![],
+                },
+                Control {
+                    id: CID_AUTO_WHITE_BALANCE,
+                    name: "White Balance, Automatic".into(),
+                    data: CtrlData::Boolean {
+                        value: true,
+                        default: true,
+                    },
+                    flags: FLAG_UPDATE,
+                    elem_size: 4,
+                    dims: vec![],
+                },
+                Control {
+                    id: CID_POWER_LINE_FREQUENCY,
+                    name: "Power Line Frequency".into(),
+                    data: CtrlData::Menu {
+                        value: 1,
+                        default: 1,
+                        items: vec![
+                            CtrlMenuItem {
+                                index: 0,
+                                name: "Disabled".into(),
+                            },
+                            CtrlMenuItem {
+                                index: 1,
+                                name: "50 Hz".into(),
+                            },
+                            CtrlMenuItem {
+                                index: 2,
+                                name: "60 Hz".into(),
+                            },
+                        ],
+                    },
+                    flags: 0,
+                    elem_size: 4,
+                    dims: vec![],
+                },
+            ],
+            generator: Arc::new(|_, sequence, data| {
+                for byte in data.iter_mut() {
+                    *byte = sequence as u8;
+                }
+
+                data.len()
+            }),
+        }
+    }
+}
+
+impl MockDevice {
+    /// Make the backend, all descriptors opened by it share the state of the device.
+    pub fn into_backend(self) -> Arc<dyn Backend> {
+        let format = self.formats.first().map(|f| f.format).unwrap_or(*b"YUYV");
+        let values = self.controls.iter().map(|c| c.data.value()).collect();
+
+        let mock = MockBackend {
+            device: self,
+            state: Mutex::new(MockState {
+                handles: vec![],
+                format: ActualFormat {
+                    resolution: (0, 0),
+                    format,
+                    field: FIELD_NONE,
+                    bytesperline: 0,
+                    sizeimage: 0,
+                    colorspace: 0,
+                },
+                interval: (1, 30),
+                values,
+                buffers: vec![],
+                queue: VecDeque::new(),
+                streaming: false,
+                sequence: 0,
+            }),
+        };
+
+        let mut pix = v4l2::PixFormat::new((640, 480), FormatInfo::fourcc(format), FIELD_NONE);
+        let actual = mock.negotiate(&mut pix);
+        mock.state.lock().unwrap().format = actual;
+
+        Arc::new(mock)
+    }
+
+    /// Open the camera on the new backend.
+    pub fn open(self) -> io::Result<Camera> {
+        Camera::with_backend(self.into_backend(), "mock")
+    }
+}
+
+struct MockBackend {
+    device: MockDevice,
+    state: Mutex<MockState>,
+}
+
+struct MockState {
+    /// Opened descriptors with their subscriptions.
+    handles: Vec<MockHandle>,
+    format: ActualFormat,
+    interval: (u32, u32),
+    /// Current values of `MockDevice::controls`.
+    values: Vec<Option<ControlValue>>,
+    buffers: Vec<MockBuffer>,
+    /// Indexes of queued buffers.
+    queue: VecDeque<u32>,
+    streaming: bool,
+    sequence: u32,
+}
+
+impl MockState {
+    /// Frames are generated on demand, so any queued buffer can be dequeued.
+    fn is_ready(&self) -> bool {
+        self.streaming && !self.queue.is_empty()
+    }
+
+    fn handle(&mut self, fd: RawFd) -> io::Result<&mut MockHandle> {
+        self.handles
+            .iter_mut()
+            .find(|h| h.fd == fd)
+            .ok_or_else(|| errno(EBADF))
+    }
+
+    /// Notify handles subscribed to the control, except the one which has changed it.
+    fn notify_control(&mut self, origin: RawFd, control: &Control, value: &ControlValue) {
+        let qctrl = match query(control) {
+            Some(qctrl) => qctrl,
+            None => return,
+        };
+
+        let value = match qctrl.qtype {
+            v4l2::CTRL_TYPE_INTEGER64 => v4l2::EventCtrlValue {
+                value64: value.unify(),
+            },
+            v4l2::CTRL_TYPE_INTEGER
+            | v4l2::CTRL_TYPE_BOOLEAN
+            | v4l2::CTRL_TYPE_MENU
+            | v4l2::CTRL_TYPE_INTEGER_MENU
+            | v4l2::CTRL_TYPE_BITMASK => v4l2::EventCtrlValue {
+                value: value.unify() as i32,
+            },
+            _ => v4l2::EventCtrlValue { value64: 0 },
+        };
+
+        let mut timestamp = v4l2::Event::new().timestamp;
+        unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut timestamp) };
+
+        let subscription = (v4l2::EVENT_CTRL, control.id);
+
+        for handle in &mut self.handles {
+            if handle.fd == origin || !handle.subscriptions.contains(&subscription) {
+                continue;
+            }
+
+            let mut event = v4l2::Event::new();
+            event.etype = v4l2::EVENT_CTRL;
+            event.id = control.id;
+            event.sequence = handle.sequence;
+            event.timestamp = timestamp;
+            event.u.ctrl = v4l2::EventCtrl {
+                changes: EVENT_CTRL_CH_VALUE,
+                ctype: qctrl.qtype,
+                value,
+                flags: qctrl.flags,
+                minimum: qctrl.minimum as i32,
+                maximum: qctrl.maximum as i32,
+                step: qctrl.step as i32,
+                default_value: qctrl.default_value as i32,
+            };
+
+            handle.sequence = handle.sequence.wrapping_add(1);
+            handle.events.push_back(event);
+        }
+    }
+}
+
+/// File handle: events are subscribed and queued per descriptor.
+struct MockHandle {
+    /// Eventfd, which is readable while a frame can be dequeued.
+    fd: RawFd,
+    /// Eventfd, which is readable while an event is pending, it stands for `POLLPRI`.
+    events_fd: RawFd,
+    /// Types and ids of subscribed events.
+    subscriptions: Vec<(u32, u32)>,
+    events: VecDeque<v4l2::Event>,
+    /// Sequence number of the next event.
+    sequence: u32,
+}
+
+struct MockBuffer {
+    length: usize,
+    /// Address of the mapped memory or `0`.
+    ptr: usize,
+}
+
+fn errno(code: i32) -> io::Error {
+    io::Error::from_raw_os_error(code)
+}
+
+fn bytes_per_pixel(format: [u8; 4]) -> u32 {
+    match &format {
+        b"GREY" => 1,
+        b"RGB3" | b"BGR3" => 3,
+        b"RGB4" | b"BGR4" | b"XR24" | b"AR24" | b"XB24" | b"AB24" => 4,
+        _ => 2,
+    }
+}
+
+fn supports(info: &ResolutionInfo, resolution: (u32, u32)) -> bool {
+    match *info {
+        ResolutionInfo::Discretes(ref d) => d.contains(&resolution),
+        ResolutionInfo::Stepwise { min, max, step } => {
+            modes::fits(resolution.0, min.0, max.0, step.0)
+                && modes::fits(resolution.1, min.1, max.1, step.1)
+        }
+    }
+}
+
+fn distance(a: (u32, u32), b: (u32, u32)) -> u64 {
+    u64::from(a.0.max(b.0) - a.0.min(b.0)) + u64::from(a.1.max(b.1) - a.1.min(b.1))
+}
+
+/// Clamp the number to the range and round it to the step as `v4l2-ctrls` does.
+fn round_to_range(value: i64, minimum: i64, maximum: i64, step: i64) -> i64 {
+    let value = value.max(minimum).min(maximum);
+
+    if step <= 1 {
+        return value;
+    }
+
+    let value = minimum + (value - minimum + step / 2) / step * step;
+
+    if value > maximum {
+        value - step
+    } else {
+        value
+    }
+}
+
+/// Out of the range is `ERANGE`, skipped items inside of it are `EINVAL`.
+fn menu_item(index: i64, indexes: Vec<u32>) -> io::Result<ControlValue> {
+    let minimum = indexes.iter().min().map_or(0, |&i| i64::from(i));
+    let maximum = indexes.iter().max().map_or(0, |&i| i64::from(i));
+
+    if index < minimum || index > maximum {
+        return Err(errno(ERANGE));
+    }
+
+    if !indexes.iter().any(|&i| i64::from(i) == index) {
+        return Err(errno(EINVAL));
+    }
+
+    Ok(ControlValue::Menu(index as u32))
+}
+
+/// Validate the new value as `v4l2-ctrls` does: numbers are adjusted,
+/// wrong menu items and strings are rejected.
+fn validate(data: &CtrlData, value: ControlValue) -> io::Result<ControlValue> {
+    let round_all = |elements: Vec<u32>, minimum: u32, maximum: u32, step: u32| {
+        elements
+            .into_iter()
+            .map(|e| {
+                let (minimum, maximum, step) =
+                    (i64::from(minimum), i64::from(maximum), i64::from(step));
+                round_to_range(i64::from(e), minimum, maximum, step) as u32
+            })
+            .collect::<Vec<_>>()
+    };
+
+    Ok(match *data {
+        CtrlData::Integer {
+            minimum,
+            maximum,
+            step,
+            ..
+        } => {
+            let (minimum, maximum, step) =
+                (i64::from(minimum), i64::from(maximum), i64::from(step));
+            ControlValue::Integer(round_to_range(value.unify(), minimum, maximum, step) as i32)
+        }
+        CtrlData::Integer64 {
+            minimum,
+            maximum,
+            step,
+            ..
+        } => ControlValue::Integer64(round_to_range(value.unify(), minimum, maximum, step)),
+        CtrlData::Boolean { .. } => ControlValue::Boolean(value.unify() != 0),
+        CtrlData::Menu { ref items, .. } => {
+            menu_item(value.unify(), items.iter().map(|i| i.index).collect())?
+        }
+        CtrlData::IntegerMenu { ref items, .. } => {
+            menu_item(value.unify(), items.iter().map(|i| i.index).collect())?
+        }
+        CtrlData::Bitmask { maximum, .. } => ControlValue::Bitmask(value.unify() as u32 & maximum),
+        CtrlData::Button => ControlValue::Button,
+        CtrlData::String {
+            minimum,
+            maximum,
+            step,
+            ..
+        } => {
+            let len = match value {
+                ControlValue::String(ref s) => s.len() as u32,
+                _ => return Err(errno(EINVAL)),
+            };
+
+            if !modes::fits(len, minimum, maximum, step) {
+                return Err(errno(ERANGE));
+            }
+
+            value
+        }
+        CtrlData::U8Array {
+            minimum,
+            maximum,
+            step,
+            ..
+        } => {
+            let elements = value.elements().ok_or_else(|| errno(EINVAL))?;
+            let (minimum, maximum, step) =
+                (u32::from(minimum), u32::from(maximum), u32::from(step));
+            let elements = round_all(elements, minimum, maximum, step);
+            ControlValue::U8Array(elements.into_iter().map(|e| e as u8).collect())
+        }
+        CtrlData::U16Array {
+            minimum,
+            maximum,
+            step,
+            ..
+        } => {
+            let elements = value.elements().ok_or_else(|| errno(EINVAL))?;
+            let (minimum, maximum, step) =
+                (u32::from(minimum), u32::from(maximum), u32::from(step));
+            let elements = round_all(elements, minimum, maximum, step);
+            ControlValue::U16Array(elements.into_iter().map(|e| e as u16).collect())
+        }
+        CtrlData::U32Array {
+            minimum,
+            maximum,
+            step,
+            ..
+        } => {
+            let elements = value.elements().ok_or_else(|| errno(EINVAL))?;
+            ControlValue::U32Array(round_all(elements, minimum, maximum, step))
+        }
+        CtrlData::Area { .. } => value,
+        CtrlData::CtrlClass | CtrlData::Unknown => return Err(errno(EINVAL)),
+    })
+}
+
+/// Describe the control as `VIDIOC_QUERY_EXT_CTRL` does, `None` for `CtrlData::Unknown`.
+fn query(ctrl: &Control) -> Option<v4l2::QueryExtCtrl> {
+    let mut qctrl = v4l2::QueryExtCtrl::new(ctrl.id);
+    v4l2::copy_name(&mut qctrl.name, ctrl.name.as_bytes());
+    qctrl.flags = ctrl.flags;
+
+    let menu_range = |indexes: &mut dyn Iterator<Item = u32>| {
+        let indexes = indexes.collect::<Vec<_>>();
+        let minimum = indexes.iter().cloned().min().unwrap_or(0);
+        let maximum = indexes.iter().cloned().max().unwrap_or(0);
+        (i64::from(minimum), i64::from(maximum))
+    };
+
+    let (qtype, minimum, maximum, step, default, elem_size, elems) = match ctrl.data {
+        CtrlData::Integer {
+            default,
+            minimum,
+            maximum,
+            step,
+            ..
+        } => (
+            v4l2::CTRL_TYPE_INTEGER,
+            i64::from(minimum),
+            i64::from(maximum),
+            step as u64,
+            i64::from(default),
+            4,
+            0,
+        ),
+        CtrlData::Boolean { default, .. } => {
+            (v4l2::CTRL_TYPE_BOOLEAN, 0, 1, 1, default as i64, 4, 0)
+        }
+        CtrlData::Menu {
+            default, ref items, ..
+        } => {
+            let (minimum, maximum) = menu_range(&mut items.iter().map(|i| i.index));
+            let default = i64::from(default);
+            (v4l2::CTRL_TYPE_MENU, minimum, maximum, 1, default, 4, 0)
+        }
+        CtrlData::Button => (v4l2::CTRL_TYPE_BUTTON, 0, 0, 0, 0, 4, 0),
+        CtrlData::Integer64 {
+            default,
+            minimum,
+            maximum,
+            step,
+            ..
+        } => (
+            v4l2::CTRL_TYPE_INTEGER64,
+            minimum,
+            maximum,
+            step as u64,
+            default,
+            8,
+            0,
+        ),
+        CtrlData::CtrlClass => (v4l2::CTRL_TYPE_CTRL_CLASS, 0, 0, 0, 0, 0, 0),
+        CtrlData::String {
+            minimum,
+            maximum,
+            step,
+            ..
+        } => (
+            v4l2::CTRL_TYPE_STRING,
+            i64::from(minimum),
+            i64::from(maximum),
+            u64::from(step),
+            0,
+            maximum + 1,
+            0,
+        ),
+        CtrlData::Bitmask {
+            default, maximum, ..
+        } => (
+            v4l2::CTRL_TYPE_BITMASK,
+            0,
+            i64::from(maximum),
+            0,
+            i64::from(default),
+            4,
+            0,
+        ),
+        CtrlData::IntegerMenu {
+            default, ref items, ..
+        } => {
+            let (minimum, maximum) = menu_range(&mut items.iter().map(|i| i.index));
+            let default = i64::from(default);
+            (
+                v4l2::CTRL_TYPE_INTEGER_MENU,
+                minimum,
+                maximum,
+                1,
+                default,
+                4,
+                0,
+            )
+        }
+        CtrlData::U8Array {
+            ref value,
+            default,
+            minimum,
+            maximum,
+            step,
+        } => (
+            v4l2::CTRL_TYPE_U8,
+            i64::from(minimum),
+            i64::from(maximum),
+            u64::from(step),
+            i64::from(default),
+            1,
+            value.len(),
+        ),
+        CtrlData::U16Array {
+            ref value,
+            default,
+            minimum,
+            maximum,
+            step,
+        } => (
+            v4l2::CTRL_TYPE_U16,
+            i64::from(minimum),
+            i64::from(maximum),
+            u64::from(step),
+            i64::from(default),
+            2,
+            value.len(),
+        ),
+        CtrlData::U32Array {
+            ref value,
+            default,
+            minimum,
+            maximum,
+            step,
+        } => (
+            v4l2::CTRL_TYPE_U32,
+            i64::from(minimum),
+            i64::from(maximum),
+            u64::from(step),
+            i64::from(default),
+            4,
+            value.len(),
+        ),
+        CtrlData::Area { .. } => (v4l2::CTRL_TYPE_AREA, 0, 0, 0, 0, 8, 0),
+        CtrlData::Unknown => return None,
+    };
+
+    qctrl.qtype = qtype;
+    qctrl.minimum = minimum;
+    qctrl.maximum = maximum;
+    qctrl.step = step;
+    qctrl.default_value = default;
+    qctrl.elem_size = elem_size;
+    qctrl.elems = 1;
+
+    if qtype == v4l2::CTRL_TYPE_STRING || qtype >= v4l2::CTRL_TYPE_U8 {
+        qctrl.flags |= FLAG_HAS_PAYLOAD;
+    }
+
+    // Arrays have at least one dimension.
+    if qtype >= v4l2::CTRL_TYPE_U8 && qtype != v4l2::CTRL_TYPE_AREA {
+        let dims = if ctrl.dims.is_empty() {
+            vec![elems as u32]
+        } else {
+            ctrl.dims.clone()
+        };
+
+        qctrl.nr_of_dims = dims.len().min(4) as u32;
+        qctrl.dims[..qctrl.nr_of_dims as usize].copy_from_slice(&dims[..qctrl.nr_of_dims as usize]);
+        qctrl.elems = elems as u32;
+    }
+
+    Some(qctrl)
+}
+
+impl MockBackend {
+    fn find_format(&self, fourcc: u32) -> Option<&MockFormat> {
+        self.device
+            .formats
+            .iter()
+            .find(|f| FormatInfo::fourcc(f.format) == fourcc)
+    }
+
+    /// Adjust the requested format to the nearest supported one.
+    fn negotiate(&self, pix: &mut v4l2::PixFormat) -> ActualFormat {
+        let format = match self.find_format(pix.pixelformat) {
+            Some(format) => format,
+            None => match self.device.formats.first() {
+                Some(format) => format,
+                None => return ActualFormat::new(pix),
+            },
+        };
+
+        let requested = (pix.width, pix.height);
+        let resolution = modes::candidate_resolutions(&format.resolutions, Some(requested))
+            .into_iter()
+            .min_by_key(|&r| distance(r, requested))
+            .unwrap_or(requested);
+
+        let (bytesperline, sizeimage) = if format.compressed {
+            (0, resolution.0 * resolution.1 * 2)
+        } else {
+            let bytesperline = resolution.0 * bytes_per_pixel(format.format);
+            (bytesperline, bytesperline * resolution.1)
+        };
+
+        *pix = v4l2::PixFormat::new(resolution, FormatInfo::fourcc(format.format), FIELD_NONE);
+        pix.bytesperline = bytesperline;
+        pix.sizeimage = sizeimage;
+
+        ActualFormat::new(pix)
+    }
+
+    /// Find the control by id, possibly the next one (see `NEXT_CTRL`).
+    fn find_control(&self, id: u32) -> Option<usize> {
+        let flags = id & (v4l2::NEXT_CTRL | v4l2::NEXT_COMPOUND);
+        let id = id & !flags;
+
+        if flags == 0 {
+            return self.device.controls.iter().position(|c| c.id == id);
+        }
+
+        self.device
+            .controls
+            .iter()
+            .enumerate()
+            .filter(|&(_, c)| c.id > id)
+            .filter(|&(_, c)| match query(c) {
+                Some(qctrl) if qctrl.qtype >= v4l2::CTRL_TYPE_U8 => {
+                    flags & v4l2::NEXT_COMPOUND != 0
+                }
+                Some(_) => flags & v4l2::NEXT_CTRL != 0,
+                None => false,
+            })
+            .min_by_key(|&(_, c)| c.id)
+            .map(|(index, _)| index)
+    }
+
+    unsafe fn handle(
+        &self,
+        state: &mut MockState,
+        fd: RawFd,
+        request: usize,
+        arg: *mut c_void,
+    ) -> io::Result<()> {
+        match request {
+            v4l2::VIDIOC_QUERYCAP => {
+                let cap = &mut *(arg as *mut v4l2::Capability);
+                *cap = v4l2::Capability::new();
+                v4l2::copy_name(&mut cap.driver, b"rscam-mock");
+                v4l2::copy_name(&mut cap.card, self.device.name.as_bytes());
+                v4l2::copy_name(&mut cap.bus_info, b"mock");
+                cap.version = 1 << 16;
+                cap.device_caps = CAP_VIDEO_CAPTURE | CAP_STREAMING;
+                cap.capabilities = cap.device_caps | CAP_DEVICE_CAPS;
+            }
+            v4l2::VIDIOC_ENUM_FMT => {
+                let desc = &mut *(arg as *mut v4l2::FmtDesc);
+                let format = self
+                    .device
+                    .formats
+                    .get(desc.index as usize)
+                    .ok_or_else(|| errno(EINVAL))?;
+
+                desc.pixelformat = FormatInfo::fourcc(format.format);
+                desc.description = [0; 32];
+                v4l2::copy_name(&mut desc.description, format.description.as_bytes());
+                desc.flags = if format.compressed {
+                    v4l2::FMT_FLAG_COMPRESSED
+                } else {
+                    0
+                };
+            }
+            v4l2::VIDIOC_ENUM_FRAMESIZES => {
+                let size = &mut *(arg as *mut v4l2::Frmsizeenum);
+                let format = self
+                    .find_format(size.pixelformat)
+                    .ok_or_else(|| errno(EINVAL))?;
+
+                match format.resolutions {
+                    ResolutionInfo::Discretes(ref d) => {
+                        let resolution = d.get(size.index as usize).ok_or_else(|| errno(EINVAL))?;
+                        size.ftype = v4l2::FRMSIZE_TYPE_DISCRETE;
+                        size.discrete().width = resolution.0;
+                        size.discrete().height = resolution.1;
+                    }
+                    ResolutionInfo::Stepwise { min, max, step } if size.index == 0 => {
+                        size.ftype = v4l2::FRMSIZE_TYPE_STEPWISE;
+                        let sw = size.stepwise();
+                        sw.min_width = min.0;
+                        sw.min_height = min.1;
+                        sw.max_width = max.0;
+                        sw.max_height = max.1;
+                        sw.step_width = step.0;
+                        sw.step_height = step.1;
+                    }
+                    ResolutionInfo::Stepwise { .. } => return Err(errno(EINVAL)),
+                }
+            }
+            v4l2::VIDIOC_ENUM_FRAMEINTERVALS => {
+                let ival = &mut *(arg as *mut v4l2::Frmivalenum);
+                let format = self
+                    .find_format(ival.pixelformat)
+                    .ok_or_else(|| errno(EINVAL))?;
+
+                if !supports(&format.resolutions, (ival.width, ival.height)) {
+                    return Err(errno(EINVAL));
+                }
+
+                match format.intervals {
+                    IntervalInfo::Discretes(ref d) => {
+                        let interval = d.get(ival.index as usize).ok_or_else(|| errno(EINVAL))?;
+                        ival.ftype = v4l2::FRMIVAL_TYPE_DISCRETE;
+                        ival.discrete().numerator = interval.0;
+                        ival.discrete().denominator = interval.1;
+                    }
+                    IntervalInfo::Stepwise { min, max, step } if ival.index == 0 => {
+                        ival.ftype = v4l2::FRMIVAL_TYPE_STEPWISE;
+                        let sw = ival.stepwise();
+                        sw.min.numerator = min.0;
+                        sw.min.denominator = min.1;
+                        sw.max.numerator = max.0;
+                        sw.max.denominator = max.1;
+                        sw.step.numerator = step.0;
+                        sw.step.denominator = step.1;
+                    }
+                    IntervalInfo::Stepwise { .. } => return Err(errno(EINVAL)),
+                }
+            }
+            v4l2::VIDIOC_G_FMT => {
+                let fmt = &mut *(arg as *mut v4l2::Format);
+                let actual = state.format;
+                fmt.fmt = v4l2::PixFormat::new(
+                    actual.resolution,
+                    FormatInfo::fourcc(actual.format),
+                    actual.field,
+                );
+                fmt.fmt.bytesperline = actual.bytesperline;
+                fmt.fmt.sizeimage = actual.sizeimage;
+            }
+            v4l2::VIDIOC_S_FMT => {
+                if state.streaming || !state.buffers.is_empty() {
+                    return Err(errno(EBUSY));
+                }
+
+                let fmt = &mut *(arg as *mut v4l2::Format);
+                state.format = self.negotiate(&mut fmt.fmt);
+            }
+            v4l2::VIDIOC_TRY_FMT => {
+                let fmt = &mut *(arg as *mut v4l2::Format);
+                self.negotiate(&mut fmt.fmt);
+            }
+            v4l2::VIDIOC_S_PARM => {
+                let parm = &mut *(arg as *mut v4l2::StreamParm);
+                let time = &mut parm.parm.timeperframe;
+                let requested = (time.numerator, time.denominator);
+
+                let intervals = self
+                    .find_format(FormatInfo::fourcc(state.format.format))
+                    .map(|f| f.intervals.clone())
+                    .unwrap_or_else(|| IntervalInfo::Discretes(vec![state.interval]));
+
+                let rate = |interval| modes::mfps(interval) as i64;
+
+                let interval = match intervals {
+                    IntervalInfo::Discretes(ref d) => d
+                        .iter()
+                        .cloned()
+                        .min_by_key(|&i| (rate(i) - rate(requested)).abs())
+                        .unwrap_or(state.interval),
+                    IntervalInfo::Stepwise { min, max, .. } => {
+                        if rate(requested) > rate(min) || requested.0 == 0 {
+                            min
+                        } else if rate(requested) < rate(max) {
+                            max
+                        } else {
+                            requested
+                        }
+                    }
+                };
+
+                state.interval = interval;
+                time.numerator = interval.0;
+                time.denominator = interval.1;
+                parm.parm.capability = v4l2::CAP_TIMEPERFRAME;
+            }
+            v4l2::VIDIOC_REQBUFS => {
+                let req = &mut *(arg as *mut v4l2::RequestBuffers);
+
+                if state.streaming || state.buffers.iter().any(|b| b.ptr != 0) {
+                    return Err(errno(EBUSY));
+                }
+
+                let length = state.format.sizeimage as usize;
+                state.queue.clear();
+                state.buffers = (0..req.count.min(32))
+                    .map(|_| MockBuffer { length, ptr: 0 })
+                    .collect();
+
+                req.count = state.buffers.len() as u32;
+            }
+            v4l2::VIDIOC_QUERYBUF => {
+                let buf = &mut *(arg as *mut v4l2::Buffer);
+                let buffer = state
+                    .buffers
+                    .get(buf.index as usize)
+                    .ok_or_else(|| errno(EINVAL))?;
+
+                buf.length = buffer.length as u32;
+                buf.m = buf.index as usize;
+            }
+            v4l2::VIDIOC_QBUF => {
+                let buf = &mut *(arg as *mut v4l2::Buffer);
+
+                if buf.index as usize >= state.buffers.len() || state.queue.contains(&buf.index) {
+                    return Err(errno(EINVAL));
+                }
+
+                state.queue.push_back(buf.index);
+            }
+            v4l2::VIDIOC_DQBUF => {
+                let buf = &mut *(arg as *mut v4l2::Buffer);
+
+                if !state.streaming {
+                    return Err(errno(EINVAL));
+                }
+
+                let index = state.queue.pop_front().ok_or_else(|| errno(EAGAIN))?;
+                let buffer = &state.buffers[index as usize];
+                let sequence = state.sequence;
+
+                let bytesused = if buffer.ptr != 0 {
+                    let data = slice::from_raw_parts_mut(buffer.ptr as *mut u8, buffer.length);
+                    (self.device.generator)(&state.format, sequence, data).min(buffer.length)
+                } else {
+                    0
+                };
+
+                let (num, den) = state.interval;
+                let micros =
+                    u128::from(sequence) * u128::from(num) * 1_000_000 / u128::from(den.max(1));
+                let micros = micros.min(u64::MAX as u128) as u64;
+
+                buf.index = index;
+                buf.bytesused = bytesused as u32;
+                buf.field = state.format.field;
+                buf.sequence = sequence;
+                buf.timestamp.tv_sec = (micros / 1_000_000) as _;
+                buf.timestamp.tv_usec = (micros % 1_000_000) as _;
+                buf.length = buffer.length as u32;
+                buf.m = index as usize;
+
+                state.sequence = state.sequence.wrapping_add(1);
+            }
+            v4l2::VIDIOC_STREAMON => {
+                if state.buffers.is_empty() {
+                    return Err(errno(EINVAL));
+                }
+
+                state.streaming = true;
+                state.sequence = 0;
+            }
+            v4l2::VIDIOC_STREAMOFF => {
+                state.streaming = false;
+                state.queue.clear();
+            }
+            v4l2::VIDIOC_QUERYCTRL => {
+                let qctrl = &mut *(arg as *mut v4l2::QueryCtrl);
+                let index = self.find_control(qctrl.id).ok_or_else(|| errno(EINVAL))?;
+                let qectrl = query(&self.device.controls[index]).ok_or_else(|| errno(EINVAL))?;
+
+                qctrl.id = qectrl.id;
+                qctrl.qtype = qectrl.qtype;
+                qctrl.name = qectrl.name;
+                qctrl.minimum = qectrl.minimum as i32;
+                qctrl.maximum = qectrl.maximum as i32;
+                qctrl.step = qectrl.step as i32;
+                qctrl.default_value = qectrl.default_value as i32;
+                qctrl.flags = qectrl.flags;
+            }
+            v4l2::VIDIOC_QUERY_EXT_CTRL => {
+                let qctrl = &mut *(arg as *mut v4l2::QueryExtCtrl);
+                let index = self.find_control(qctrl.id).ok_or_else(|| errno(EINVAL))?;
+                *qctrl = query(&self.device.controls[index]).ok_or_else(|| errno(EINVAL))?;
+            }
+            v4l2::VIDIOC_QUERYMENU => {
+                let qmenu = &mut *(arg as *mut v4l2::QueryMenu);
+                let (id, index) = (qmenu.id, qmenu.index);
+                let ctrl = self
+                    .device
+                    .controls
+                    .iter()
+                    .find(|c| c.id == id)
+                    .ok_or_else(|| errno(EINVAL))?;
+
+                match ctrl.data {
+                    CtrlData::Menu { ref items, .. } => {
+                        let item = items.iter().find(|i| i.index == index);
+                        let item = item.ok_or_else(|| errno(EINVAL))?;
+                        qmenu.data.set_name(item.name.as_bytes());
+                    }
+                    CtrlData::IntegerMenu { ref items, .. } => {
+                        let item = items.iter().find(|i| i.index == index);
+                        let item = item.ok_or_else(|| errno(EINVAL))?;
+                        qmenu.data.set_value(item.value);
+                    }
+                    _ => return Err(errno(EINVAL)),
+                }
+            }
+            v4l2::VIDIOC_G_CTRL => {
+                let ctrl = &mut *(arg as *mut v4l2::Control);
+                let index = self.find_control(ctrl.id).ok_or_else(|| errno(EINVAL))?;
+
+                match state.values[index] {
+                    Some(ControlValue::String(_)) | Some(ControlValue::Compound(_)) => {
+                        return Err(errno(EINVAL))
+                    }
+                    Some(ref value) if value.elements().is_none() => {
+                        ctrl.value = value.unify() as i32
+                    }
+                    Some(_) => return Err(errno(EINVAL)),
+                    None => return Err(errno(EACCES)),
+                }
+            }
+            v4l2::VIDIOC_G_EXT_CTRLS | v4l2::VIDIOC_S_EXT_CTRLS | v4l2::VIDIOC_TRY_EXT_CTRLS => {
+                let ext = &mut *(arg as *mut v4l2::ExtControls<'_>);
+                let mut values = vec![];
+
+                let count = ext.count;
+
+                for (i, ctrl) in ext.controls().iter_mut().enumerate() {
+                    if let Err(err) = self.ext_control(state, request, ctrl, &mut values) {
+                        // Nothing is applied if validation fails, so no control is pointed out.
+                        ext.error_idx = if request == v4l2::VIDIOC_S_EXT_CTRLS {
+                            count
+                        } else {
+                            i as u32
+                        };
+
+                        return Err(err);
+                    }
+                }
+
+                if request == v4l2::VIDIOC_S_EXT_CTRLS {
+                    for (index, value) in values {
+                        if value == ControlValue::Button
+                            || state.values[index].as_ref() == Some(&value)
+                        {
+                            continue;
+                        }
+
+                        state.notify_control(fd, &self.device.controls[index], &value);
+                        state.values[index] = Some(value);
+                    }
+                }
+            }
+            v4l2::VIDIOC_SUBSCRIBE_EVENT => {
+                let sub = &*(arg as *const v4l2::EventSubscription);
+
+                // Like uvcvideo, which supports only control events.
+                if sub.etype != v4l2::EVENT_CTRL || self.find_control(sub.id).is_none() {
+                    return Err(errno(EINVAL));
+                }
+
+                let handle = state.handle(fd)?;
+
+                if !handle.subscriptions.contains(&(sub.etype, sub.id)) {
+                    handle.subscriptions.push((sub.etype, sub.id));
+                }
+            }
+            v4l2::VIDIOC_UNSUBSCRIBE_EVENT => {
+                let sub = &*(arg as *const v4l2::EventSubscription);
+                let handle = state.handle(fd)?;
+
+                // Pending events of the subscription are dropped too.
+                handle.subscriptions.retain(|&s| s != (sub.etype, sub.id));
+                handle
+                    .events
+                    .retain(|e| (e.etype, e.id) != (sub.etype, sub.id));
+            }
+            v4l2::VIDIOC_DQEVENT => {
+                let handle = state.handle(fd)?;
+                let mut event = handle.events.pop_front().ok_or_else(|| errno(ENOENT))?;
+
+                event.pending = handle.events.len() as u32;
+                *(arg as *mut v4l2::Event) = event;
+            }
+            _ => return Err(errno(ENOTTY)),
+        }
+
+        Ok(())
+    }
+
+    /// Get the value of the control or check the new one, which is applied later.
+    unsafe fn ext_control(
+        &self,
+        state: &MockState,
+        request: usize,
+        ctrl: &mut v4l2::ExtControl,
+        values: &mut Vec<(usize, ControlValue)>,
+    ) -> io::Result<()> {
+        let index = self.find_control(ctrl.id).ok_or_else(|| errno(EINVAL))?;
+        let control = &self.device.controls[index];
+        let qctrl = query(control).ok_or_else(|| errno(EINVAL))?;
+
+        if request == v4l2::VIDIOC_G_EXT_CTRLS {
+            let value = state.values[index].as_ref().ok_or_else(|| errno(EACCES))?;
+
+            match value.marshal(&qctrl) {
+                Some(Some(payload)) => {
+                    if (ctrl.size as usize) < payload.len() {
+                        ctrl.size = payload.len() as u32;
+                        return Err(errno(ENOSPC));
+                    }
+
+                    let dst = ctrl.value as usize as *mut u8;
+                    ptr::copy_nonoverlapping(payload.as_ptr(), dst, payload.len());
+                }
+                Some(None) => ctrl.value = value.unify(),
+                None => return Err(errno(EINVAL)),
+            }
+
+            return Ok(());
+        }
+
+        // Like uvcvideo, which refuses controls made inactive by automatic modes.
+        if control.flags & (FLAG_READ_ONLY | FLAG_INACTIVE) != 0 {
+            return Err(errno(EACCES));
+        }
+
+        let value = if qctrl.flags & FLAG_HAS_PAYLOAD != 0 {
+            let src = ctrl.value as usize as *const u8;
+            let payload = slice::from_raw_parts(src, ctrl.size as usize).to_vec();
+            ControlValue::unmarshal(&qctrl, 0, Some(payload))
+        } else {
+            ControlValue::unmarshal(&qctrl, ctrl.value, None)
+        };
+
+        let value = validate(&control.data, value)?;
+
+        // The adjusted value is returned to the caller.
+        match value.marshal(&qctrl) {
+            Some(Some(payload)) => {
+                let dst = ctrl.value as usize as *mut u8;
+                let len = payload.len().min(ctrl.size as usize);
+                ptr::copy_nonoverlapping(payload.as_ptr(), dst, len);
+            }
+            Some(None) => ctrl.value = value.unify(),
+            None => return Err(errno(EINVAL)),
+        }
+
+        values.push((index, value));
+        Ok(())
+    }
+}
+
+impl Backend for MockBackend {
+    fn open(&self, _path: &str) -> io::Result<RawFd> {
+        let mut state = self.state.lock().unwrap();
+        let fd = v4l2::eventfd()?;
+
+        let events_fd = match v4l2::eventfd() {
+            Ok(events_fd) => events_fd,
+            Err(err) => {
+                v4l2::close_eventfd(fd);
+                return Err(err);
+            }
+        };
+
+        v4l2::set_ready(fd, state.is_ready());
+        state.handles.push(MockHandle {
+            fd,
+            events_fd,
+            subscriptions: vec![],
+            events: VecDeque::new(),
+            sequence: 0,
+        });
+
+        Ok(fd)
+    }
+
+    fn close(&self, fd: RawFd) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let index = state
+            .handles
+            .iter()
+            .position(|h| h.fd == fd)
+            .ok_or_else(|| errno(EBADF))?;
+
+        let handle = state.handles.remove(index);
+        v4l2::close_eventfd(handle.fd);
+        v4l2::close_eventfd(handle.events_fd);
+        Ok(())
+    }
+
+    unsafe fn ioctl(&self, fd: RawFd, request: usize, arg: *mut c_void) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+
+        state.handle(fd)?;
+
+        let result = self.handle(&mut state, fd, request, arg);
+        let ready = state.is_ready();
+
+        for handle in &state.handles {
+            v4l2::set_ready(handle.fd, ready);
+            v4l2::set_ready(handle.events_fd, !handle.events.is_empty());
+        }
+
+        result
+    }
+
+    fn mmap(&self, fd: RawFd, length: usize, offset: usize) -> io::Result<*mut u8> {
+        let mut state = self.state.lock().unwrap();
+        state.handle(fd)?;
+
+        let buffer = state
+            .buffers
+            .get_mut(offset)
+            .filter(|b| b.ptr == 0 && b.length == length)
+            .ok_or_else(|| errno(EINVAL))?;
+
+        // The memory outlives the buffer if frames are still alive, it's freed by `munmap()`.
+        let memory = vec![0u8; length].into_boxed_slice();
+        buffer.ptr = Box::into_raw(memory) as *mut u8 as usize;
+
+        Ok(buffer.ptr as *mut u8)
+    }
+
+    unsafe fn munmap(&self, ptr: *mut u8, length: usize) {
+        let mut state = self.state.lock().unwrap();
+
+        for buffer in state.buffers.iter_mut().filter(|b| b.ptr == ptr as usize) {
+            buffer.ptr = 0;
+        }
+
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, length)));
+    }
+
+    fn poll(&self, fd: RawFd, events: i16, timeout: Option<Duration>) -> io::Result<bool> {
+        let events_fd = self.state.lock().unwrap().handle(fd)?.events_fd;
+        let mut fds = vec![];
+
+        if events & v4l2::POLLIN != 0 {
+            fds.push(pollfd {
+                fd,
+                events: v4l2::POLLIN,
+                revents: 0,
+            });
+        }
+
+        if events & v4l2::POLLPRI != 0 {
+            fds.push(pollfd {
+                fd: events_fd,
+                events: v4l2::POLLIN,
+                revents: 0,
+            });
+        }
+
+        // Another thread can queue a buffer or an event while waiting, so the lock isn't held.
+        v4l2::poll_fds(&mut fds, timeout)
+    }
+}
+
+impl Drop for MockBackend {
+    fn drop(&mut self) {
+        for handle in &self.state.get_mut().unwrap().handles {
+            v4l2::close_eventfd(handle.fd);
+            v4l2::close_eventfd(handle.events_fd);
+        }
+    }
+}
+
+#[test]
+fn test_capture() {
+    use crate::{Config, Error, Negotiation};
+    use std::os::unix::io::AsRawFd;
+
+    let mut camera = MockDevice::default().open().unwrap();
+
+    assert!(camera.capabilities().unwrap().is_capture());
+    assert_eq!(camera.formats().count(), 2);
+    assert_eq!(camera.modes().unwrap()[0].resolutions.len(), 2);
+
+    let format = camera
+        .start(&Config {
+            interval: (1, 30),
+            resolution: (1280, 720),
+            ..Default::default()
+        })
+        .unwrap();
+
+    assert_eq!(format.bytesperline, 2560);
+
+    let first = camera.capture().unwrap();
+    let second = camera.capture().unwrap();
+
+    assert_eq!(first.len(), 1280 * 720 * 2);
+    assert!(first.iter().all(|&b| b == 0));
+    assert!(second.iter().all(|&b| b == 1));
+    assert_eq!(second.get_timestamp() - first.get_timestamp(), 33_333);
+
+    // All buffers are held by frames.
+    assert!(camera.try_capture().unwrap().is_none());
+    assert!(!v4l2::poll(
+        camera.as_raw_fd(),
+        v4l2::POLLIN,
+        Some(Duration::from_millis(0))
+    )
+    .unwrap());
+    drop(first);
+    assert!(v4l2::poll(camera.as_raw_fd(), v4l2::POLLIN, None).unwrap());
+    assert!(camera.try_capture().unwrap().is_some());
+    drop(second);
+
+    camera.stop().unwrap();
+
+    match camera.start(&Config {
+        resolution: (1000, 700),
+        format: b"MJPG",
+        ..Default::default()
+    }) {
+        Err(Error::BadResolution) => {}
+        res => panic!("unexpected {:?}", res.map(|_| ())),
+    }
+
+    let format = camera
+        .start(&Config {
+            resolution: (1000, 700),
+            format: b"MJPG",
+            negotiation: Negotiation::Nearest,
+            ..Default::default()
+        })
+        .unwrap();
+
+    assert_eq!(format.resolution, (992, 696));
+    assert_eq!(camera.interval(), Some((1, 30)));
+}
+
+#[test]
+fn test_modes() {
+    use crate::ModePreferences;
+
+    let mut device = MockDevice::default();
+    device.formats[0].intervals = IntervalInfo::Discretes(vec![]);
+    let camera = device.open().unwrap();
+
+    let modes = camera.modes().unwrap();
+    assert!(modes[0].resolutions.iter().all(|r| r.intervals.is_none()));
+    assert!(modes[1].resolutions.iter().all(|r| r.intervals.is_some()));
+    assert!(format!("{:?}", modes).contains("intervals: None"));
+
+    let mode = camera.select_mode(&ModePreferences::default()).unwrap();
+    assert_eq!(mode.unwrap().format, *b"MJPG");
+
+    assert_eq!(
+        format!("{:?}", ResolutionInfo::Discretes(vec![])),
+        "Discretes:"
+    );
+    assert_eq!(
+        format!("{:?}", IntervalInfo::Discretes(vec![(1, 30), (1, 15)])),
+        "Discretes: 30fps, 15fps"
+    );
+}
+
+#[test]
+fn test_controls() {
+    use crate::Error;
+
+    let camera = MockDevice::default().open().unwrap();
+
+    assert_eq!(camera.controls().count(), 3);
+
+    camera.set_control(CID_BRIGHTNESS, 200).unwrap();
+    camera.set_control(CID_AUTO_WHITE_BALANCE, false).unwrap();
+
+    match camera.get_control(CID_BRIGHTNESS).unwrap().data {
+        CtrlData::Integer { value: 200, .. } => {}
+        data => panic!("unexpected {:?}", data),
+    }
+
+    // Out of the range values are clamped by the driver.
+    camera.set_control(CID_BRIGHTNESS, 300).unwrap();
+
+    match camera.get_control(CID_BRIGHTNESS).unwrap().data {
+        CtrlData::Integer { value: 255, .. } => {}
+        data => panic!("unexpected {:?}", data),
+    }
+
+    let tried = camera
+        .try_controls(&[(CID_BRIGHTNESS, ControlValue::Integer(10))])
+        .unwrap();
+    assert_eq!(tried, vec![(CID_BRIGHTNESS, ControlValue::Integer(10))]);
+
+    let invalid = [
+        (CID_BRIGHTNESS, ControlValue::Integer(-5)),
+        (CID_POWER_LINE_FREQUENCY, ControlValue::Menu(5)),
+        (
+            CID_POWER_LINE_FREQUENCY,
+            ControlValue::String("50 Hz".into()),
+        ),
+    ];
+
+    for (id, value) in invalid {
+        match camera.try_controls(&[(id, value)]) {
+            Err(Error::BadControlValue { id: rejected, .. }) => assert_eq!(rejected, id),
+            res => panic!("unexpected {:?}", res),
+        }
+    }
+
+    // Nothing is applied if one of the values is invalid.
+    let controls = [
+        (CID_BRIGHTNESS, ControlValue::Integer(10)),
+        (CID_POWER_LINE_FREQUENCY, ControlValue::Menu(5)),
+    ];
+
+    match camera.set_controls(&controls) {
+        Err(Error::ControlRejected {
+            id: CID_POWER_LINE_FREQUENCY,
+            source,
+        }) => assert_eq!(source.raw_os_error(), Some(ERANGE)),
+        res => panic!("unexpected {:?}", res),
+    }
+
+    match camera.get_control(CID_BRIGHTNESS).unwrap().data {
+        CtrlData::Integer { value: 255, .. } => {}
+        data => panic!("unexpected {:?}", data),
+    }
+
+    let profile = camera.snapshot_controls().unwrap();
+    assert_eq!(profile.controls[1].value, ControlValue::Boolean(false));
+
+    let ctrl = camera
+        .control_by_name("power_line_frequency")
+        .unwrap()
+        .unwrap();
+    assert_eq!(ctrl.data.value(), Some(ControlValue::Menu(1)));
+}
+
+#[test]
+fn test_events() {
+    use crate::{EventData, EventKind};
+    use std::thread;
+
+    let backend = MockDevice::default().into_backend();
+    let camera = Camera::with_backend(backend.clone(), "mock").unwrap();
+    let other = Camera::with_backend(backend, "mock").unwrap();
+
+    camera
+        .subscribe(EventKind::Control(CID_BRIGHTNESS))
+        .unwrap();
+    assert!(camera.subscribe(EventKind::Eos).is_err());
+    assert_eq!(camera.dequeue_event().unwrap(), None);
+
+    // Neither own, nor unchanged, nor unsubscribed changes are reported.
+    camera.set_control(CID_BRIGHTNESS, 10).unwrap();
+    other.set_control(CID_BRIGHTNESS, 10).unwrap();
+    other.set_control(CID_AUTO_WHITE_BALANCE, false).unwrap();
+    assert_eq!(camera.dequeue_event().unwrap(), None);
+
+    other.set_control(CID_BRIGHTNESS, 20).unwrap();
+    other.set_control(CID_BRIGHTNESS, 300).unwrap();
+
+    let event = camera.dequeue_event().unwrap().unwrap();
+    assert_eq!((event.pending, event.sequence), (1, 0));
+
+    match event.data {
+        EventData::Control(ctrl) => {
+            assert_eq!(ctrl.id, CID_BRIGHTNESS);
+            assert_eq!(ctrl.changes, EVENT_CTRL_CH_VALUE);
+            assert_eq!(ctrl.value, Some(ControlValue::Integer(20)));
+            assert_eq!((ctrl.minimum, ctrl.maximum, ctrl.default), (0, 255, 128));
+            assert_eq!(ctrl.flags, FLAG_SLIDER);
+        }
+        data => panic!("unexpected {:?}", data),
+    }
+
+    let event = camera.wait_event(None).unwrap().unwrap();
+    assert_eq!((event.pending, event.sequence), (0, 1));
+
+    match event.data {
+        EventData::Control(ctrl) => assert_eq!(ctrl.value, Some(ControlValue::Integer(255))),
+        data => panic!("unexpected {:?}", data),
+    }
+
+    let timeout = Some(Duration::from_millis(10));
+    assert_eq!(camera.wait_event(timeout).unwrap(), None);
+
+    // The event wakes up the waiting camera.
+    let setter = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        other.set_control(CID_BRIGHTNESS, 30).unwrap();
+        other
+    });
+
+    let event = camera.wait_event(Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(event.map(|e| e.sequence), Some(2));
+
+    let other = setter.join().unwrap();
+    camera
+        .unsubscribe(EventKind::Control(CID_BRIGHTNESS))
+        .unwrap();
+    other.set_control(CID_BRIGHTNESS, 40).unwrap();
+    assert_eq!(camera.dequeue_event().unwrap(), None);
+}
+
+#[test]
+fn test_capture_or_event() {
+    use crate::{Captured, Config, EventData, EventKind};
+    use std::thread;
+
+    let backend = MockDevice::default().into_backend();
+    let mut camera = Camera::with_backend(backend.clone(), "mock").unwrap();
+    let other = Camera::with_backend(backend, "mock").unwrap();
+
+    camera
+        .subscribe(EventKind::Control(CID_BRIGHTNESS))
+        .unwrap();
+    camera
+        .start(&Config {
+            interval: (1, 30),
+            ..Default::default()
+        })
+        .unwrap();
+
+    // Pending events go before frames.
+    other.set_control(CID_BRIGHTNESS, 10).unwrap();
+
+    match camera.capture_or_event().unwrap() {
+        Captured::Event(event) => assert!(matches!(event.data, EventData::Control(_))),
+        Captured::Frame(_) => panic!("unexpected frame"),
+    }
+
+    let mut frames = vec![];
+
+    match camera.capture_or_event().unwrap() {
+        Captured::Frame(frame) => frames.push(frame),
+        Captured::Event(event) => panic!("unexpected {:?}", event),
+    }
+
+    // All buffers are borrowed, so only the event can wake up the camera.
+    while let Some(frame) = camera.try_capture().unwrap() {
+        frames.push(frame);
+    }
+
+    assert!(camera.try_capture_or_event().unwrap().is_none());
+
+    let setter = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        other.set_control(CID_BRIGHTNESS, 20).unwrap();
+    });
+
+    match camera.capture_or_event().unwrap() {
+        Captured::Event(event) => assert_eq!(event.sequence, 1),
+        Captured::Frame(_) => panic!("unexpected frame"),
+    }
+
+    setter.join().unwrap();
+    let borrowed = frames.len() as u32;
+    frames.clear();
+
+    match camera.capture_or_event().unwrap() {
+        Captured::Frame(frame) => assert_eq!(frame.sequence(), borrowed),
+        Captured::Event(event) => panic!("unexpected {:?}", event),
+    }
+}
+
+#[test]
+fn test_borrowed_frames() {
+    use crate::{Captured, Config, Error};
+
+    let config = Config {
+        interval: (1, 30),
+        ..Default::default()
+    };
+
+    let mut camera = MockDevice::default().open().unwrap();
+    camera.start(&config).unwrap();
+
+    let frame = camera.capture().unwrap();
+    camera.stop().unwrap();
+
+    match camera.start(&config) {
+        Err(Error::FramesBorrowed) => {}
+        res => panic!("unexpected {:?}", res.map(|_| ())),
+    }
+
+    assert!(!camera.is_streaming());
+    drop(frame);
+
+    camera.start(&config).unwrap();
+    assert_eq!(camera.capture().unwrap().sequence(), 0);
+
+    match camera.capture_or_event().unwrap() {
+        Captured::Frame(frame) => assert_eq!(frame.sequence(), 1),
+        Captured::Event(event) => panic!("unexpected {:?}", event),
+    }
+}
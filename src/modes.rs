@@ -0,0 +1,253 @@
+use std::cmp::Reverse;
+
+#[cfg(feature = "serde")]
+use crate::fourcc;
+use crate::{Config, FormatInfo, IntervalInfo, ResolutionInfo};
+
+/// Preferences for `Camera::select_mode()`.
+///
+/// Modes are compared by the rank of the format in `formats` first, then by the distance
+/// to `resolution`, then by compression (if `prefer_uncompressed`) and by frame rate.
+#[derive(Debug, Clone, Default)]
+pub struct ModePreferences {
+    /// FourCCs of formats in order of preference. Other formats are ranked lower.
+    /// Default is empty.
+    pub formats: Vec<[u8; 4]>,
+    /// Width and height of frame to get as close as possible to.
+    /// Default is `None`, the largest resolution.
+    pub resolution: Option<(u32, u32)>,
+    /// Modes with lower frame rate are rejected.
+    /// Default is `0`.
+    pub min_fps: u32,
+    /// Prefer raw formats to compressed ones (e.g. `YUYV` to `MJPG`).
+    /// Default is `false`.
+    pub prefer_uncompressed: bool,
+}
+
+/// Combination of format, resolution and interval supported by the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mode {
+    /// FourCC of format.
+    #[cfg_attr(feature = "serde", serde(with = "fourcc"))]
+    pub format: [u8; 4],
+    /// Width and height of frame.
+    pub resolution: (u32, u32),
+    /// Frame interval as `(numerator, denominator)`.
+    pub interval: (u32, u32),
+}
+
+impl Mode {
+    /// Make the config to start streaming in this mode.
+    pub fn config(&self) -> Config<'_> {
+        Config {
+            interval: self.interval,
+            resolution: self.resolution,
+            format: &self.format,
+            ..Default::default()
+        }
+    }
+}
+
+/// All resolutions of the format with their intervals, see `Camera::modes()`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FormatModes {
+    pub format: FormatInfo,
+    /// Resolutions as reported by the device, `None` if it refuses to enumerate them.
+    pub sizes: Option<ResolutionInfo>,
+    /// Discrete resolutions, stepwise ones are expanded.
+    pub resolutions: Vec<ResolutionModes>,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResolutionModes {
+    pub resolution: (u32, u32),
+    /// `None` if the device refuses to enumerate intervals of the resolution.
+    pub intervals: Option<IntervalInfo>,
+}
+
+/// Widespread resolutions to try in stepwise ranges.
+const COMMON_RESOLUTIONS: [(u32, u32); 12] = [
+    (160, 120),
+    (320, 240),
+    (640, 480),
+    (800, 600),
+    (1024, 768),
+    (1280, 720),
+    (1280, 960),
+    (1600, 1200),
+    (1920, 1080),
+    (2560, 1440),
+    (3840, 2160),
+    (4096, 2160),
+];
+
+/// Frame rate in millihertz, it's enough to compare intervals.
+pub(crate) fn mfps(interval: (u32, u32)) -> u64 {
+    match interval {
+        (0, _) => 0,
+        (num, den) => u64::from(den) * 1000 / u64::from(num),
+    }
+}
+
+fn snap(target: u32, min: u32, max: u32, step: u32) -> u32 {
+    let value = target.max(min).min(max);
+
+    match (value - min).checked_div(step) {
+        Some(steps) => min + steps * step,
+        None => value,
+    }
+}
+
+/// Resolutions worth trying: all discretes or the nearest to the target in the range.
+pub(crate) fn candidate_resolutions(
+    info: &ResolutionInfo,
+    target: Option<(u32, u32)>,
+) -> Vec<(u32, u32)> {
+    match *info {
+        ResolutionInfo::Discretes(ref d) => d.clone(),
+        ResolutionInfo::Stepwise { min, max, step } => {
+            let target = target.unwrap_or(max);
+            vec![(
+                snap(target.0, min.0, max.0, step.0),
+                snap(target.1, min.1, max.1, step.1),
+            )]
+        }
+    }
+}
+
+/// Whether the value is in the range and on the step, zero step means any value.
+pub(crate) fn fits<T: Into<i64>>(value: T, min: T, max: T, step: T) -> bool {
+    let (value, min, max, step) = (value.into(), min.into(), max.into(), step.into());
+    value >= min && value <= max && (value - min).checked_rem(step).unwrap_or(0) == 0
+}
+
+/// All discretes or the minimum, the maximum and common resolutions in the range.
+pub(crate) fn expand_resolutions(info: &ResolutionInfo) -> Vec<(u32, u32)> {
+    match *info {
+        ResolutionInfo::Discretes(ref d) => d.clone(),
+        ResolutionInfo::Stepwise { min, max, step } => {
+            let mut resolutions = vec![min];
+
+            resolutions.extend(
+                COMMON_RESOLUTIONS.iter().cloned().filter(|&(w, h)| {
+                    fits(w, min.0, max.0, step.0) && fits(h, min.1, max.1, step.1)
+                }),
+            );
+
+            resolutions.push(max);
+            resolutions.dedup();
+            resolutions
+        }
+    }
+}
+
+/// The interval with the highest frame rate.
+pub(crate) fn fastest_interval(info: &IntervalInfo) -> Option<(u32, u32)> {
+    match *info {
+        IntervalInfo::Discretes(ref d) => d.iter().cloned().max_by_key(|&i| mfps(i)),
+        IntervalInfo::Stepwise { min, .. } => Some(min),
+    }
+    .filter(|&i| mfps(i) > 0)
+}
+
+impl ModePreferences {
+    /// Whether the mode is acceptable at all.
+    pub(crate) fn accepts(&self, interval: (u32, u32)) -> bool {
+        mfps(interval) >= u64::from(self.min_fps) * 1000
+    }
+
+    /// Lower is better.
+    pub(crate) fn score(
+        &self,
+        format: &FormatInfo,
+        resolution: (u32, u32),
+        interval: (u32, u32),
+    ) -> impl Ord {
+        let distance = match self.resolution {
+            Some((w, h)) => {
+                u64::from(w.max(resolution.0) - w.min(resolution.0))
+                    + u64::from(h.max(resolution.1) - h.min(resolution.1))
+            }
+            None => u64::MAX - u64::from(resolution.0) * u64::from(resolution.1),
+        };
+
+        let rank = self
+            .formats
+            .iter()
+            .position(|f| *f == format.format)
+            .unwrap_or(self.formats.len());
+
+        let compressed = self.prefer_uncompressed && format.compressed;
+
+        (rank, distance, compressed, Reverse(mfps(interval)))
+    }
+}
+
+#[test]
+fn test_select_mode() {
+    let yuyv = FormatInfo::new(FormatInfo::fourcc(*b"YUYV"), b"YUYV 4:2:2", 0);
+    let mjpg = FormatInfo::new(FormatInfo::fourcc(*b"MJPG"), b"Motion-JPEG", 1);
+
+    let stepwise = ResolutionInfo::Stepwise {
+        min: (160, 120),
+        max: (1920, 1080),
+        step: (16, 8),
+    };
+
+    assert_eq!(candidate_resolutions(&stepwise, None), vec![(1920, 1080)]);
+    assert_eq!(
+        candidate_resolutions(&stepwise, Some((1000, 4000))),
+        vec![(992, 1080)]
+    );
+
+    let intervals = IntervalInfo::Discretes(vec![(1, 10), (1, 30), (1, 15)]);
+    assert_eq!(fastest_interval(&intervals), Some((1, 30)));
+
+    let mut prefs = ModePreferences {
+        resolution: Some((1280, 720)),
+        min_fps: 15,
+        ..Default::default()
+    };
+
+    assert!(prefs.accepts((1, 15)));
+    assert!(!prefs.accepts((1, 10)));
+
+    assert!(prefs.score(&yuyv, (1280, 720), (1, 10)) < prefs.score(&mjpg, (1920, 1080), (1, 30)));
+    assert!(prefs.score(&mjpg, (1280, 720), (1, 30)) < prefs.score(&mjpg, (1280, 720), (1, 15)));
+
+    prefs.prefer_uncompressed = true;
+    assert!(prefs.score(&yuyv, (1280, 720), (1, 10)) < prefs.score(&mjpg, (1280, 720), (1, 30)));
+
+    prefs.formats = vec![*b"MJPG"];
+    assert!(prefs.score(&mjpg, (1280, 720), (1, 10)) < prefs.score(&yuyv, (1280, 720), (1, 30)));
+    assert!(prefs.score(&mjpg, (1920, 1080), (1, 30)) < prefs.score(&yuyv, (1280, 720), (1, 30)));
+}
+
+#[test]
+fn test_expand_resolutions() {
+    let stepwise = ResolutionInfo::Stepwise {
+        min: (160, 120),
+        max: (1920, 1080),
+        step: (16, 8),
+    };
+
+    assert_eq!(
+        expand_resolutions(&stepwise),
+        vec![
+            (160, 120),
+            (320, 240),
+            (640, 480),
+            (800, 600),
+            (1024, 768),
+            (1280, 720),
+            (1280, 960),
+            (1920, 1080)
+        ]
+    );
+
+    let discretes = ResolutionInfo::Discretes(vec![(640, 480), (320, 240)]);
+    assert_eq!(expand_resolutions(&discretes), vec![(640, 480), (320, 240)]);
+}
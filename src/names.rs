@@ -0,0 +1,909 @@
+use crate::consts::*;
+
+/// Canonical names of standard controls: names reported by the kernel converted as v4l2-ctl
+/// does (see `normalize_name()`). Controls named by drivers or sharing the name with another one
+/// (e.g. RDS of transmitters and receivers) are named after `CID_*` constants without the prefix.
+const NAMES: [(u32, &str); 301] = [
+    (CID_USER_CLASS, "user_controls"),
+    (CID_BRIGHTNESS, "brightness"),
+    (CID_CONTRAST, "contrast"),
+    (CID_SATURATION, "saturation"),
+    (CID_HUE, "hue"),
+    (CID_AUDIO_VOLUME, "volume"),
+    (CID_AUDIO_BALANCE, "balance"),
+    (CID_AUDIO_BASS, "bass"),
+    (CID_AUDIO_TREBLE, "treble"),
+    (CID_AUDIO_MUTE, "mute"),
+    (CID_AUDIO_LOUDNESS, "loudness"),
+    (CID_BLACK_LEVEL, "black_level"),
+    (CID_AUTO_WHITE_BALANCE, "white_balance_automatic"),
+    (CID_DO_WHITE_BALANCE, "do_white_balance"),
+    (CID_RED_BALANCE, "red_balance"),
+    (CID_BLUE_BALANCE, "blue_balance"),
+    (CID_GAMMA, "gamma"),
+    (CID_EXPOSURE, "exposure"),
+    (CID_AUTOGAIN, "gain_automatic"),
+    (CID_GAIN, "gain"),
+    (CID_HFLIP, "horizontal_flip"),
+    (CID_VFLIP, "vertical_flip"),
+    (CID_POWER_LINE_FREQUENCY, "power_line_frequency"),
+    (CID_HUE_AUTO, "hue_automatic"),
+    (CID_WHITE_BALANCE_TEMPERATURE, "white_balance_temperature"),
+    (CID_SHARPNESS, "sharpness"),
+    (CID_BACKLIGHT_COMPENSATION, "backlight_compensation"),
+    (CID_CHROMA_AGC, "chroma_agc"),
+    (CID_COLOR_KILLER, "color_killer"),
+    (CID_COLORFX, "color_effects"),
+    (CID_AUTOBRIGHTNESS, "brightness_automatic"),
+    (CID_BAND_STOP_FILTER, "band_stop_filter"),
+    (CID_ROTATE, "rotate"),
+    (CID_BG_COLOR, "background_color"),
+    (CID_CHROMA_GAIN, "chroma_gain"),
+    (CID_ILLUMINATORS_1, "illuminator_1"),
+    (CID_ILLUMINATORS_2, "illuminator_2"),
+    (CID_MIN_BUFFERS_FOR_CAPTURE, "min_number_of_capture_buffers"),
+    (CID_MIN_BUFFERS_FOR_OUTPUT, "min_number_of_output_buffers"),
+    (CID_ALPHA_COMPONENT, "alpha_component"),
+    (CID_COLORFX_CBCR, "color_effects_cbcr"),
+    (CID_MPEG_CLASS, "codec_controls"),
+    (CID_MPEG_STREAM_TYPE, "stream_type"),
+    (CID_MPEG_STREAM_PID_PMT, "stream_pmt_program_id"),
+    (CID_MPEG_STREAM_PID_AUDIO, "stream_audio_program_id"),
+    (CID_MPEG_STREAM_PID_VIDEO, "stream_video_program_id"),
+    (CID_MPEG_STREAM_PID_PCR, "stream_pcr_program_id"),
+    (CID_MPEG_STREAM_PES_ID_AUDIO, "stream_pes_audio_id"),
+    (CID_MPEG_STREAM_PES_ID_VIDEO, "stream_pes_video_id"),
+    (CID_MPEG_STREAM_VBI_FMT, "stream_vbi_format"),
+    (CID_MPEG_AUDIO_SAMPLING_FREQ, "audio_sampling_frequency"),
+    (CID_MPEG_AUDIO_ENCODING, "audio_encoding"),
+    (CID_MPEG_AUDIO_L1_BITRATE, "audio_layer_i_bitrate"),
+    (CID_MPEG_AUDIO_L2_BITRATE, "audio_layer_ii_bitrate"),
+    (CID_MPEG_AUDIO_L3_BITRATE, "audio_layer_iii_bitrate"),
+    (CID_MPEG_AUDIO_MODE, "audio_stereo_mode"),
+    (CID_MPEG_AUDIO_MODE_EXTENSION, "audio_stereo_mode_extension"),
+    (CID_MPEG_AUDIO_EMPHASIS, "audio_emphasis"),
+    (CID_MPEG_AUDIO_CRC, "audio_crc"),
+    (CID_MPEG_AUDIO_MUTE, "audio_mute"),
+    (CID_MPEG_AUDIO_AAC_BITRATE, "audio_aac_bitrate"),
+    (CID_MPEG_AUDIO_AC3_BITRATE, "audio_ac_3_bitrate"),
+    (CID_MPEG_AUDIO_DEC_PLAYBACK, "audio_playback"),
+    (
+        CID_MPEG_AUDIO_DEC_MULTILINGUAL_PLAYBACK,
+        "audio_multilingual_playback",
+    ),
+    (CID_MPEG_VIDEO_ENCODING, "video_encoding"),
+    (CID_MPEG_VIDEO_ASPECT, "video_aspect"),
+    (CID_MPEG_VIDEO_B_FRAMES, "video_b_frames"),
+    (CID_MPEG_VIDEO_GOP_SIZE, "video_gop_size"),
+    (CID_MPEG_VIDEO_GOP_CLOSURE, "video_gop_closure"),
+    (CID_MPEG_VIDEO_PULLDOWN, "video_pulldown"),
+    (CID_MPEG_VIDEO_BITRATE_MODE, "video_bitrate_mode"),
+    (CID_MPEG_VIDEO_BITRATE, "video_bitrate"),
+    (CID_MPEG_VIDEO_BITRATE_PEAK, "video_peak_bitrate"),
+    (
+        CID_MPEG_VIDEO_TEMPORAL_DECIMATION,
+        "video_temporal_decimation",
+    ),
+    (CID_MPEG_VIDEO_MUTE, "video_mute"),
+    (CID_MPEG_VIDEO_MUTE_YUV, "video_mute_yuv"),
+    (
+        CID_MPEG_VIDEO_DECODER_SLICE_INTERFACE,
+        "decoder_slice_interface",
+    ),
+    (
+        CID_MPEG_VIDEO_DECODER_MPEG4_DEBLOCK_FILTER,
+        "mpeg4_loop_filter_enable",
+    ),
+    (
+        CID_MPEG_VIDEO_CYCLIC_INTRA_REFRESH_MB,
+        "number_of_intra_refresh_mbs",
+    ),
+    (
+        CID_MPEG_VIDEO_FRAME_RC_ENABLE,
+        "frame_level_rate_control_enable",
+    ),
+    (CID_MPEG_VIDEO_HEADER_MODE, "sequence_header_mode"),
+    (CID_MPEG_VIDEO_MAX_REF_PIC, "max_number_of_reference_pics"),
+    (CID_MPEG_VIDEO_MB_RC_ENABLE, "mb_level_rate_control"),
+    (
+        CID_MPEG_VIDEO_MULTI_SLICE_MAX_BYTES,
+        "maximum_bytes_in_a_slice",
+    ),
+    (
+        CID_MPEG_VIDEO_MULTI_SLICE_MAX_MB,
+        "number_of_mbs_in_a_slice",
+    ),
+    (CID_MPEG_VIDEO_MULTI_SLICE_MODE, "slice_partitioning_method"),
+    (CID_MPEG_VIDEO_VBV_SIZE, "vbv_buffer_size"),
+    (CID_MPEG_VIDEO_DEC_PTS, "video_decoder_pts"),
+    (CID_MPEG_VIDEO_DEC_FRAME, "video_decoder_frame_count"),
+    (CID_MPEG_VIDEO_VBV_DELAY, "initial_delay_for_vbv_control"),
+    (CID_MPEG_VIDEO_REPEAT_SEQ_HEADER, "repeat_sequence_header"),
+    (
+        CID_MPEG_VIDEO_MV_H_SEARCH_RANGE,
+        "horizontal_mv_search_range",
+    ),
+    (CID_MPEG_VIDEO_MV_V_SEARCH_RANGE, "vertical_mv_search_range"),
+    (CID_MPEG_VIDEO_H263_I_FRAME_QP, "h263_i_frame_qp_value"),
+    (CID_MPEG_VIDEO_H263_P_FRAME_QP, "h263_p_frame_qp_value"),
+    (CID_MPEG_VIDEO_H263_B_FRAME_QP, "h263_b_frame_qp_value"),
+    (CID_MPEG_VIDEO_H263_MIN_QP, "h263_minimum_qp_value"),
+    (CID_MPEG_VIDEO_H263_MAX_QP, "h263_maximum_qp_value"),
+    (CID_MPEG_VIDEO_H264_I_FRAME_QP, "h264_i_frame_qp_value"),
+    (CID_MPEG_VIDEO_H264_P_FRAME_QP, "h264_p_frame_qp_value"),
+    (CID_MPEG_VIDEO_H264_B_FRAME_QP, "h264_b_frame_qp_value"),
+    (CID_MPEG_VIDEO_H264_MIN_QP, "h264_minimum_qp_value"),
+    (CID_MPEG_VIDEO_H264_MAX_QP, "h264_maximum_qp_value"),
+    (
+        CID_MPEG_VIDEO_H264_8X8_TRANSFORM,
+        "h264_8x8_transform_enable",
+    ),
+    (CID_MPEG_VIDEO_H264_CPB_SIZE, "h264_cpb_buffer_size"),
+    (CID_MPEG_VIDEO_H264_ENTROPY_MODE, "h264_entropy_mode"),
+    (CID_MPEG_VIDEO_H264_I_PERIOD, "h264_i_frame_period"),
+    (CID_MPEG_VIDEO_H264_LEVEL, "h264_level"),
+    (
+        CID_MPEG_VIDEO_H264_LOOP_FILTER_ALPHA,
+        "h264_loop_filter_alpha_offset",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_LOOP_FILTER_BETA,
+        "h264_loop_filter_beta_offset",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_LOOP_FILTER_MODE,
+        "h264_loop_filter_mode",
+    ),
+    (CID_MPEG_VIDEO_H264_PROFILE, "h264_profile"),
+    (
+        CID_MPEG_VIDEO_H264_VUI_EXT_SAR_HEIGHT,
+        "vertical_size_of_sar",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_VUI_EXT_SAR_WIDTH,
+        "horizontal_size_of_sar",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_VUI_SAR_ENABLE,
+        "aspect_ratio_vui_enable",
+    ),
+    (CID_MPEG_VIDEO_H264_VUI_SAR_IDC, "vui_aspect_ratio_idc"),
+    (
+        CID_MPEG_VIDEO_H264_SEI_FRAME_PACKING,
+        "h264_enable_frame_packing_sei",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_SEI_FP_CURRENT_FRAME_0,
+        "h264_set_curr_frame_as_frame0",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_SEI_FP_ARRANGEMENT_TYPE,
+        "h264_fp_arrangement_type",
+    ),
+    (CID_MPEG_VIDEO_H264_FMO, "h264_flexible_mb_ordering"),
+    (CID_MPEG_VIDEO_H264_FMO_MAP_TYPE, "h264_map_type_for_fmo"),
+    (
+        CID_MPEG_VIDEO_H264_FMO_SLICE_GROUP,
+        "h264_fmo_number_of_slice_groups",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_FMO_CHANGE_DIRECTION,
+        "h264_fmo_direction_of_change",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_FMO_CHANGE_RATE,
+        "h264_fmo_size_of_1st_slice_grp",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_FMO_RUN_LENGTH,
+        "h264_fmo_no_of_consecutive_mbs",
+    ),
+    (CID_MPEG_VIDEO_H264_ASO, "h264_arbitrary_slice_ordering"),
+    (CID_MPEG_VIDEO_H264_ASO_SLICE_ORDER, "h264_aso_slice_order"),
+    (
+        CID_MPEG_VIDEO_H264_HIERARCHICAL_CODING,
+        "enable_h264_hierarchical_coding",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_HIERARCHICAL_CODING_TYPE,
+        "h264_hierarchical_coding_type",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_HIERARCHICAL_CODING_LAYER,
+        "h264_number_of_hc_layers",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_HIERARCHICAL_CODING_LAYER_QP,
+        "h264_set_qp_value_for_hc_layers",
+    ),
+    (CID_MPEG_VIDEO_MPEG4_I_FRAME_QP, "mpeg4_i_frame_qp_value"),
+    (CID_MPEG_VIDEO_MPEG4_P_FRAME_QP, "mpeg4_p_frame_qp_value"),
+    (CID_MPEG_VIDEO_MPEG4_B_FRAME_QP, "mpeg4_b_frame_qp_value"),
+    (CID_MPEG_VIDEO_MPEG4_MIN_QP, "mpeg4_minimum_qp_value"),
+    (CID_MPEG_VIDEO_MPEG4_MAX_QP, "mpeg4_maximum_qp_value"),
+    (CID_MPEG_VIDEO_MPEG4_LEVEL, "mpeg4_level"),
+    (CID_MPEG_VIDEO_MPEG4_PROFILE, "mpeg4_profile"),
+    (CID_MPEG_VIDEO_MPEG4_QPEL, "quarter_pixel_search_enable"),
+    (
+        CID_MPEG_VIDEO_VPX_NUM_PARTITIONS,
+        "vpx_number_of_partitions",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_IMD_DISABLE_4X4,
+        "vpx_intra_mode_decision_disable",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_NUM_REF_FRAMES,
+        "vpx_no_of_refs_for_p_frame",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_FILTER_LEVEL,
+        "vpx_loop_filter_level_range",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_FILTER_SHARPNESS,
+        "vpx_deblocking_effect_control",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_GOLDEN_FRAME_REF_PERIOD,
+        "vpx_golden_frame_refresh_period",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_GOLDEN_FRAME_SEL,
+        "vpx_golden_frame_indicator",
+    ),
+    (CID_MPEG_VIDEO_VPX_MIN_QP, "vpx_minimum_qp_value"),
+    (CID_MPEG_VIDEO_VPX_MAX_QP, "vpx_maximum_qp_value"),
+    (CID_MPEG_VIDEO_VPX_I_FRAME_QP, "vpx_i_frame_qp_value"),
+    (CID_MPEG_VIDEO_VPX_P_FRAME_QP, "vpx_p_frame_qp_value"),
+    (CID_MPEG_VIDEO_VPX_PROFILE, "vp8_profile"),
+    (
+        CID_MPEG_CX2341X_VIDEO_SPATIAL_FILTER_MODE,
+        "mpeg_cx2341x_video_spatial_filter_mode",
+    ),
+    (
+        CID_MPEG_CX2341X_VIDEO_SPATIAL_FILTER,
+        "mpeg_cx2341x_video_spatial_filter",
+    ),
+    (
+        CID_MPEG_CX2341X_VIDEO_LUMA_SPATIAL_FILTER_TYPE,
+        "mpeg_cx2341x_video_luma_spatial_filter_type",
+    ),
+    (
+        CID_MPEG_CX2341X_VIDEO_CHROMA_SPATIAL_FILTER_TYPE,
+        "mpeg_cx2341x_video_chroma_spatial_filter_type",
+    ),
+    (
+        CID_MPEG_CX2341X_VIDEO_TEMPORAL_FILTER_MODE,
+        "mpeg_cx2341x_video_temporal_filter_mode",
+    ),
+    (
+        CID_MPEG_CX2341X_VIDEO_TEMPORAL_FILTER,
+        "mpeg_cx2341x_video_temporal_filter",
+    ),
+    (
+        CID_MPEG_CX2341X_VIDEO_MEDIAN_FILTER_TYPE,
+        "mpeg_cx2341x_video_median_filter_type",
+    ),
+    (
+        CID_MPEG_CX2341X_VIDEO_LUMA_MEDIAN_FILTER_BOTTOM,
+        "mpeg_cx2341x_video_luma_median_filter_bottom",
+    ),
+    (
+        CID_MPEG_CX2341X_VIDEO_LUMA_MEDIAN_FILTER_TOP,
+        "mpeg_cx2341x_video_luma_median_filter_top",
+    ),
+    (
+        CID_MPEG_CX2341X_VIDEO_CHROMA_MEDIAN_FILTER_BOTTOM,
+        "mpeg_cx2341x_video_chroma_median_filter_bottom",
+    ),
+    (
+        CID_MPEG_CX2341X_VIDEO_CHROMA_MEDIAN_FILTER_TOP,
+        "mpeg_cx2341x_video_chroma_median_filter_top",
+    ),
+    (
+        CID_MPEG_CX2341X_STREAM_INSERT_NAV_PACKETS,
+        "mpeg_cx2341x_stream_insert_nav_packets",
+    ),
+    (
+        CID_MPEG_MFC51_VIDEO_DECODER_H264_DISPLAY_DELAY,
+        "mpeg_mfc51_video_decoder_h264_display_delay",
+    ),
+    (
+        CID_MPEG_MFC51_VIDEO_DECODER_H264_DISPLAY_DELAY_ENABLE,
+        "mpeg_mfc51_video_decoder_h264_display_delay_enable",
+    ),
+    (
+        CID_MPEG_MFC51_VIDEO_FRAME_SKIP_MODE,
+        "mpeg_mfc51_video_frame_skip_mode",
+    ),
+    (
+        CID_MPEG_MFC51_VIDEO_FORCE_FRAME_TYPE,
+        "mpeg_mfc51_video_force_frame_type",
+    ),
+    (CID_MPEG_MFC51_VIDEO_PADDING, "mpeg_mfc51_video_padding"),
+    (
+        CID_MPEG_MFC51_VIDEO_PADDING_YUV,
+        "mpeg_mfc51_video_padding_yuv",
+    ),
+    (
+        CID_MPEG_MFC51_VIDEO_RC_FIXED_TARGET_BIT,
+        "mpeg_mfc51_video_rc_fixed_target_bit",
+    ),
+    (
+        CID_MPEG_MFC51_VIDEO_RC_REACTION_COEFF,
+        "mpeg_mfc51_video_rc_reaction_coeff",
+    ),
+    (
+        CID_MPEG_MFC51_VIDEO_H264_ADAPTIVE_RC_ACTIVITY,
+        "mpeg_mfc51_video_h264_adaptive_rc_activity",
+    ),
+    (
+        CID_MPEG_MFC51_VIDEO_H264_ADAPTIVE_RC_DARK,
+        "mpeg_mfc51_video_h264_adaptive_rc_dark",
+    ),
+    (
+        CID_MPEG_MFC51_VIDEO_H264_ADAPTIVE_RC_SMOOTH,
+        "mpeg_mfc51_video_h264_adaptive_rc_smooth",
+    ),
+    (
+        CID_MPEG_MFC51_VIDEO_H264_ADAPTIVE_RC_STATIC,
+        "mpeg_mfc51_video_h264_adaptive_rc_static",
+    ),
+    (
+        CID_MPEG_MFC51_VIDEO_H264_NUM_REF_PIC_FOR_P,
+        "mpeg_mfc51_video_h264_num_ref_pic_for_p",
+    ),
+    (CID_CAMERA_CLASS, "camera_controls"),
+    (CID_EXPOSURE_AUTO, "auto_exposure"),
+    (CID_EXPOSURE_ABSOLUTE, "exposure_time_absolute"),
+    (CID_EXPOSURE_AUTO_PRIORITY, "exposure_dynamic_framerate"),
+    (CID_PAN_RELATIVE, "pan_relative"),
+    (CID_TILT_RELATIVE, "tilt_relative"),
+    (CID_PAN_RESET, "pan_reset"),
+    (CID_TILT_RESET, "tilt_reset"),
+    (CID_PAN_ABSOLUTE, "pan_absolute"),
+    (CID_TILT_ABSOLUTE, "tilt_absolute"),
+    (CID_FOCUS_ABSOLUTE, "focus_absolute"),
+    (CID_FOCUS_RELATIVE, "focus_relative"),
+    (CID_FOCUS_AUTO, "focus_automatic_continuous"),
+    (CID_ZOOM_ABSOLUTE, "zoom_absolute"),
+    (CID_ZOOM_RELATIVE, "zoom_relative"),
+    (CID_ZOOM_CONTINUOUS, "zoom_continuous"),
+    (CID_PRIVACY, "privacy"),
+    (CID_IRIS_ABSOLUTE, "iris_absolute"),
+    (CID_IRIS_RELATIVE, "iris_relative"),
+    (CID_AUTO_EXPOSURE_BIAS, "auto_exposure_bias"),
+    (CID_AUTO_N_PRESET_WHITE_BALANCE, "white_balance_auto_preset"),
+    (CID_WIDE_DYNAMIC_RANGE, "wide_dynamic_range"),
+    (CID_IMAGE_STABILIZATION, "image_stabilization"),
+    (CID_ISO_SENSITIVITY, "iso_sensitivity"),
+    (CID_ISO_SENSITIVITY_AUTO, "iso_sensitivity_auto"),
+    (CID_EXPOSURE_METERING, "exposure_metering_mode"),
+    (CID_SCENE_MODE, "scene_mode"),
+    (CID_3A_LOCK, "3a_lock"),
+    (CID_AUTO_FOCUS_START, "auto_focus_start"),
+    (CID_AUTO_FOCUS_STOP, "auto_focus_stop"),
+    (CID_AUTO_FOCUS_STATUS, "auto_focus_status"),
+    (CID_AUTO_FOCUS_RANGE, "auto_focus_range"),
+    (CID_PAN_SPEED, "pan_speed"),
+    (CID_TILT_SPEED, "tilt_speed"),
+    (CID_FM_TX_CLASS, "fm_radio_modulator_controls"),
+    (CID_RDS_TX_DEVIATION, "rds_signal_deviation"),
+    (CID_RDS_TX_PI, "rds_program_id"),
+    (CID_RDS_TX_PTY, "rds_tx_pty"),
+    (CID_RDS_TX_PS_NAME, "rds_tx_ps_name"),
+    (CID_RDS_TX_RADIO_TEXT, "rds_tx_radio_text"),
+    (CID_RDS_TX_MONO_STEREO, "rds_stereo"),
+    (CID_RDS_TX_ARTIFICIAL_HEAD, "rds_artificial_head"),
+    (CID_RDS_TX_COMPRESSED, "rds_compressed"),
+    (CID_RDS_TX_DYNAMIC_PTY, "rds_dynamic_pty"),
+    (
+        CID_RDS_TX_TRAFFIC_ANNOUNCEMENT,
+        "rds_tx_traffic_announcement",
+    ),
+    (CID_RDS_TX_TRAFFIC_PROGRAM, "rds_tx_traffic_program"),
+    (CID_RDS_TX_MUSIC_SPEECH, "rds_tx_music_speech"),
+    (CID_RDS_TX_ALT_FREQS_ENABLE, "rds_enable_alt_frequencies"),
+    (CID_RDS_TX_ALT_FREQS, "rds_alternate_frequencies"),
+    (CID_AUDIO_LIMITER_ENABLED, "audio_limiter_feature_enabled"),
+    (CID_AUDIO_LIMITER_RELEASE_TIME, "audio_limiter_release_time"),
+    (CID_AUDIO_LIMITER_DEVIATION, "audio_limiter_deviation"),
+    (CID_AUDIO_COMPRESSION_ENABLED, "audio_compression_enabled"),
+    (CID_AUDIO_COMPRESSION_GAIN, "audio_compression_gain"),
+    (
+        CID_AUDIO_COMPRESSION_THRESHOLD,
+        "audio_compression_threshold",
+    ),
+    (
+        CID_AUDIO_COMPRESSION_ATTACK_TIME,
+        "audio_compression_attack_time",
+    ),
+    (
+        CID_AUDIO_COMPRESSION_RELEASE_TIME,
+        "audio_compression_release_time",
+    ),
+    (CID_PILOT_TONE_ENABLED, "pilot_tone_feature_enabled"),
+    (CID_PILOT_TONE_DEVIATION, "pilot_tone_deviation"),
+    (CID_PILOT_TONE_FREQUENCY, "pilot_tone_frequency"),
+    (CID_TUNE_PREEMPHASIS, "pre_emphasis"),
+    (CID_TUNE_POWER_LEVEL, "tune_power_level"),
+    (CID_TUNE_ANTENNA_CAPACITOR, "tune_antenna_capacitor"),
+    (CID_FLASH_CLASS, "flash_controls"),
+    (CID_FLASH_LED_MODE, "led_mode"),
+    (CID_FLASH_STROBE_SOURCE, "strobe_source"),
+    (CID_FLASH_STROBE, "strobe"),
+    (CID_FLASH_STROBE_STOP, "stop_strobe"),
+    (CID_FLASH_STROBE_STATUS, "strobe_status"),
+    (CID_FLASH_TIMEOUT, "strobe_timeout"),
+    (CID_FLASH_INTENSITY, "intensity_flash_mode"),
+    (CID_FLASH_TORCH_INTENSITY, "intensity_torch_mode"),
+    (CID_FLASH_INDICATOR_INTENSITY, "intensity_indicator"),
+    (CID_FLASH_FAULT, "faults"),
+    (CID_FLASH_CHARGE, "charge"),
+    (CID_FLASH_READY, "ready_to_strobe"),
+    (CID_JPEG_CLASS, "jpeg_compression_controls"),
+    (CID_JPEG_CHROMA_SUBSAMPLING, "chroma_subsampling"),
+    (CID_JPEG_RESTART_INTERVAL, "restart_interval"),
+    (CID_JPEG_COMPRESSION_QUALITY, "compression_quality"),
+    (CID_JPEG_ACTIVE_MARKER, "active_markers"),
+    (CID_IMAGE_SOURCE_CLASS, "image_source_controls"),
+    (CID_VBLANK, "vertical_blanking"),
+    (CID_HBLANK, "horizontal_blanking"),
+    (CID_ANALOGUE_GAIN, "analogue_gain"),
+    (CID_TEST_PATTERN_RED, "red_pixel_value"),
+    (CID_TEST_PATTERN_GREENR, "green_red_pixel_value"),
+    (CID_TEST_PATTERN_BLUE, "blue_pixel_value"),
+    (CID_TEST_PATTERN_GREENB, "green_blue_pixel_value"),
+    (CID_IMAGE_PROC_CLASS, "image_processing_controls"),
+    (CID_LINK_FREQ, "link_frequency"),
+    (CID_PIXEL_RATE, "pixel_rate"),
+    (CID_TEST_PATTERN, "test_pattern"),
+    (CID_DV_CLASS, "digital_video_controls"),
+    (CID_DV_TX_HOTPLUG, "hotplug_present"),
+    (CID_DV_TX_RXSENSE, "rxsense_present"),
+    (CID_DV_TX_EDID_PRESENT, "edid_present"),
+    (CID_DV_TX_MODE, "transmit_mode"),
+    (CID_DV_TX_RGB_RANGE, "tx_rgb_quantization_range"),
+    (CID_DV_RX_POWER_PRESENT, "power_present"),
+    (CID_DV_RX_RGB_RANGE, "rx_rgb_quantization_range"),
+    (CID_FM_RX_CLASS, "fm_radio_receiver_controls"),
+    (CID_TUNE_DEEMPHASIS, "de_emphasis"),
+    (CID_RDS_RECEPTION, "rds_reception"),
+    (CID_RDS_RX_PTY, "rds_rx_pty"),
+    (CID_RDS_RX_PS_NAME, "rds_rx_ps_name"),
+    (CID_RDS_RX_RADIO_TEXT, "rds_rx_radio_text"),
+    (
+        CID_RDS_RX_TRAFFIC_ANNOUNCEMENT,
+        "rds_rx_traffic_announcement",
+    ),
+    (CID_RDS_RX_TRAFFIC_PROGRAM, "rds_rx_traffic_program"),
+    (CID_RDS_RX_MUSIC_SPEECH, "rds_rx_music_speech"),
+    (CID_RF_TUNER_CLASS, "rf_tuner_controls"),
+    (CID_RF_TUNER_BANDWIDTH_AUTO, "bandwidth_auto"),
+    (CID_RF_TUNER_BANDWIDTH, "bandwidth"),
+    (CID_RF_TUNER_LNA_GAIN_AUTO, "lna_gain_auto"),
+    (CID_RF_TUNER_LNA_GAIN, "lna_gain"),
+    (CID_RF_TUNER_MIXER_GAIN_AUTO, "mixer_gain_auto"),
+    (CID_RF_TUNER_MIXER_GAIN, "mixer_gain"),
+    (CID_RF_TUNER_IF_GAIN_AUTO, "if_gain_auto"),
+    (CID_RF_TUNER_IF_GAIN, "if_gain"),
+    (CID_RF_TUNER_PLL_LOCK, "pll_lock"),
+    (CID_DETECT_CLASS, "detection_controls"),
+    (CID_DETECT_MD_MODE, "motion_detection_mode"),
+    (CID_DETECT_MD_GLOBAL_THRESHOLD, "md_global_threshold"),
+    (CID_DETECT_MD_THRESHOLD_GRID, "md_threshold_grid"),
+    (CID_DETECT_MD_REGION_GRID, "md_region_grid"),
+];
+
+/// Names of `CID_*` constants without the prefix, which differ from canonical ones.
+const ALIASES: [(u32, &str); 201] = [
+    (CID_USER_CLASS, "user_class"),
+    (CID_AUDIO_VOLUME, "audio_volume"),
+    (CID_AUDIO_BALANCE, "audio_balance"),
+    (CID_AUDIO_BASS, "audio_bass"),
+    (CID_AUDIO_TREBLE, "audio_treble"),
+    (CID_AUDIO_LOUDNESS, "audio_loudness"),
+    (CID_AUTO_WHITE_BALANCE, "auto_white_balance"),
+    (CID_WHITENESS, "whiteness"),
+    (CID_AUTOGAIN, "autogain"),
+    (CID_HFLIP, "hflip"),
+    (CID_VFLIP, "vflip"),
+    (CID_HUE_AUTO, "hue_auto"),
+    (CID_COLORFX, "colorfx"),
+    (CID_AUTOBRIGHTNESS, "autobrightness"),
+    (CID_BG_COLOR, "bg_color"),
+    (CID_ILLUMINATORS_1, "illuminators_1"),
+    (CID_ILLUMINATORS_2, "illuminators_2"),
+    (CID_MIN_BUFFERS_FOR_CAPTURE, "min_buffers_for_capture"),
+    (CID_MIN_BUFFERS_FOR_OUTPUT, "min_buffers_for_output"),
+    (CID_COLORFX_CBCR, "colorfx_cbcr"),
+    (CID_MPEG_CLASS, "mpeg_class"),
+    (CID_MPEG_STREAM_TYPE, "mpeg_stream_type"),
+    (CID_MPEG_STREAM_PID_PMT, "mpeg_stream_pid_pmt"),
+    (CID_MPEG_STREAM_PID_AUDIO, "mpeg_stream_pid_audio"),
+    (CID_MPEG_STREAM_PID_VIDEO, "mpeg_stream_pid_video"),
+    (CID_MPEG_STREAM_PID_PCR, "mpeg_stream_pid_pcr"),
+    (CID_MPEG_STREAM_PES_ID_AUDIO, "mpeg_stream_pes_id_audio"),
+    (CID_MPEG_STREAM_PES_ID_VIDEO, "mpeg_stream_pes_id_video"),
+    (CID_MPEG_STREAM_VBI_FMT, "mpeg_stream_vbi_fmt"),
+    (CID_MPEG_AUDIO_SAMPLING_FREQ, "mpeg_audio_sampling_freq"),
+    (CID_MPEG_AUDIO_ENCODING, "mpeg_audio_encoding"),
+    (CID_MPEG_AUDIO_L1_BITRATE, "mpeg_audio_l1_bitrate"),
+    (CID_MPEG_AUDIO_L2_BITRATE, "mpeg_audio_l2_bitrate"),
+    (CID_MPEG_AUDIO_L3_BITRATE, "mpeg_audio_l3_bitrate"),
+    (CID_MPEG_AUDIO_MODE, "mpeg_audio_mode"),
+    (CID_MPEG_AUDIO_MODE_EXTENSION, "mpeg_audio_mode_extension"),
+    (CID_MPEG_AUDIO_EMPHASIS, "mpeg_audio_emphasis"),
+    (CID_MPEG_AUDIO_CRC, "mpeg_audio_crc"),
+    (CID_MPEG_AUDIO_MUTE, "mpeg_audio_mute"),
+    (CID_MPEG_AUDIO_AAC_BITRATE, "mpeg_audio_aac_bitrate"),
+    (CID_MPEG_AUDIO_AC3_BITRATE, "mpeg_audio_ac3_bitrate"),
+    (CID_MPEG_AUDIO_DEC_PLAYBACK, "mpeg_audio_dec_playback"),
+    (
+        CID_MPEG_AUDIO_DEC_MULTILINGUAL_PLAYBACK,
+        "mpeg_audio_dec_multilingual_playback",
+    ),
+    (CID_MPEG_VIDEO_ENCODING, "mpeg_video_encoding"),
+    (CID_MPEG_VIDEO_ASPECT, "mpeg_video_aspect"),
+    (CID_MPEG_VIDEO_B_FRAMES, "mpeg_video_b_frames"),
+    (CID_MPEG_VIDEO_GOP_SIZE, "mpeg_video_gop_size"),
+    (CID_MPEG_VIDEO_GOP_CLOSURE, "mpeg_video_gop_closure"),
+    (CID_MPEG_VIDEO_PULLDOWN, "mpeg_video_pulldown"),
+    (CID_MPEG_VIDEO_BITRATE_MODE, "mpeg_video_bitrate_mode"),
+    (CID_MPEG_VIDEO_BITRATE, "mpeg_video_bitrate"),
+    (CID_MPEG_VIDEO_BITRATE_PEAK, "mpeg_video_bitrate_peak"),
+    (
+        CID_MPEG_VIDEO_TEMPORAL_DECIMATION,
+        "mpeg_video_temporal_decimation",
+    ),
+    (CID_MPEG_VIDEO_MUTE, "mpeg_video_mute"),
+    (CID_MPEG_VIDEO_MUTE_YUV, "mpeg_video_mute_yuv"),
+    (
+        CID_MPEG_VIDEO_DECODER_SLICE_INTERFACE,
+        "mpeg_video_decoder_slice_interface",
+    ),
+    (
+        CID_MPEG_VIDEO_DECODER_MPEG4_DEBLOCK_FILTER,
+        "mpeg_video_decoder_mpeg4_deblock_filter",
+    ),
+    (
+        CID_MPEG_VIDEO_CYCLIC_INTRA_REFRESH_MB,
+        "mpeg_video_cyclic_intra_refresh_mb",
+    ),
+    (CID_MPEG_VIDEO_FRAME_RC_ENABLE, "mpeg_video_frame_rc_enable"),
+    (CID_MPEG_VIDEO_HEADER_MODE, "mpeg_video_header_mode"),
+    (CID_MPEG_VIDEO_MAX_REF_PIC, "mpeg_video_max_ref_pic"),
+    (CID_MPEG_VIDEO_MB_RC_ENABLE, "mpeg_video_mb_rc_enable"),
+    (
+        CID_MPEG_VIDEO_MULTI_SLICE_MAX_BYTES,
+        "mpeg_video_multi_slice_max_bytes",
+    ),
+    (
+        CID_MPEG_VIDEO_MULTI_SLICE_MAX_MB,
+        "mpeg_video_multi_slice_max_mb",
+    ),
+    (
+        CID_MPEG_VIDEO_MULTI_SLICE_MODE,
+        "mpeg_video_multi_slice_mode",
+    ),
+    (CID_MPEG_VIDEO_VBV_SIZE, "mpeg_video_vbv_size"),
+    (CID_MPEG_VIDEO_DEC_PTS, "mpeg_video_dec_pts"),
+    (CID_MPEG_VIDEO_DEC_FRAME, "mpeg_video_dec_frame"),
+    (CID_MPEG_VIDEO_VBV_DELAY, "mpeg_video_vbv_delay"),
+    (
+        CID_MPEG_VIDEO_REPEAT_SEQ_HEADER,
+        "mpeg_video_repeat_seq_header",
+    ),
+    (
+        CID_MPEG_VIDEO_MV_H_SEARCH_RANGE,
+        "mpeg_video_mv_h_search_range",
+    ),
+    (
+        CID_MPEG_VIDEO_MV_V_SEARCH_RANGE,
+        "mpeg_video_mv_v_search_range",
+    ),
+    (CID_MPEG_VIDEO_H263_I_FRAME_QP, "mpeg_video_h263_i_frame_qp"),
+    (CID_MPEG_VIDEO_H263_P_FRAME_QP, "mpeg_video_h263_p_frame_qp"),
+    (CID_MPEG_VIDEO_H263_B_FRAME_QP, "mpeg_video_h263_b_frame_qp"),
+    (CID_MPEG_VIDEO_H263_MIN_QP, "mpeg_video_h263_min_qp"),
+    (CID_MPEG_VIDEO_H263_MAX_QP, "mpeg_video_h263_max_qp"),
+    (CID_MPEG_VIDEO_H264_I_FRAME_QP, "mpeg_video_h264_i_frame_qp"),
+    (CID_MPEG_VIDEO_H264_P_FRAME_QP, "mpeg_video_h264_p_frame_qp"),
+    (CID_MPEG_VIDEO_H264_B_FRAME_QP, "mpeg_video_h264_b_frame_qp"),
+    (CID_MPEG_VIDEO_H264_MIN_QP, "mpeg_video_h264_min_qp"),
+    (CID_MPEG_VIDEO_H264_MAX_QP, "mpeg_video_h264_max_qp"),
+    (
+        CID_MPEG_VIDEO_H264_8X8_TRANSFORM,
+        "mpeg_video_h264_8x8_transform",
+    ),
+    (CID_MPEG_VIDEO_H264_CPB_SIZE, "mpeg_video_h264_cpb_size"),
+    (
+        CID_MPEG_VIDEO_H264_ENTROPY_MODE,
+        "mpeg_video_h264_entropy_mode",
+    ),
+    (CID_MPEG_VIDEO_H264_I_PERIOD, "mpeg_video_h264_i_period"),
+    (CID_MPEG_VIDEO_H264_LEVEL, "mpeg_video_h264_level"),
+    (
+        CID_MPEG_VIDEO_H264_LOOP_FILTER_ALPHA,
+        "mpeg_video_h264_loop_filter_alpha",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_LOOP_FILTER_BETA,
+        "mpeg_video_h264_loop_filter_beta",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_LOOP_FILTER_MODE,
+        "mpeg_video_h264_loop_filter_mode",
+    ),
+    (CID_MPEG_VIDEO_H264_PROFILE, "mpeg_video_h264_profile"),
+    (
+        CID_MPEG_VIDEO_H264_VUI_EXT_SAR_HEIGHT,
+        "mpeg_video_h264_vui_ext_sar_height",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_VUI_EXT_SAR_WIDTH,
+        "mpeg_video_h264_vui_ext_sar_width",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_VUI_SAR_ENABLE,
+        "mpeg_video_h264_vui_sar_enable",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_VUI_SAR_IDC,
+        "mpeg_video_h264_vui_sar_idc",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_SEI_FRAME_PACKING,
+        "mpeg_video_h264_sei_frame_packing",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_SEI_FP_CURRENT_FRAME_0,
+        "mpeg_video_h264_sei_fp_current_frame_0",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_SEI_FP_ARRANGEMENT_TYPE,
+        "mpeg_video_h264_sei_fp_arrangement_type",
+    ),
+    (CID_MPEG_VIDEO_H264_FMO, "mpeg_video_h264_fmo"),
+    (
+        CID_MPEG_VIDEO_H264_FMO_MAP_TYPE,
+        "mpeg_video_h264_fmo_map_type",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_FMO_SLICE_GROUP,
+        "mpeg_video_h264_fmo_slice_group",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_FMO_CHANGE_DIRECTION,
+        "mpeg_video_h264_fmo_change_direction",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_FMO_CHANGE_RATE,
+        "mpeg_video_h264_fmo_change_rate",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_FMO_RUN_LENGTH,
+        "mpeg_video_h264_fmo_run_length",
+    ),
+    (CID_MPEG_VIDEO_H264_ASO, "mpeg_video_h264_aso"),
+    (
+        CID_MPEG_VIDEO_H264_ASO_SLICE_ORDER,
+        "mpeg_video_h264_aso_slice_order",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_HIERARCHICAL_CODING,
+        "mpeg_video_h264_hierarchical_coding",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_HIERARCHICAL_CODING_TYPE,
+        "mpeg_video_h264_hierarchical_coding_type",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_HIERARCHICAL_CODING_LAYER,
+        "mpeg_video_h264_hierarchical_coding_layer",
+    ),
+    (
+        CID_MPEG_VIDEO_H264_HIERARCHICAL_CODING_LAYER_QP,
+        "mpeg_video_h264_hierarchical_coding_layer_qp",
+    ),
+    (
+        CID_MPEG_VIDEO_MPEG4_I_FRAME_QP,
+        "mpeg_video_mpeg4_i_frame_qp",
+    ),
+    (
+        CID_MPEG_VIDEO_MPEG4_P_FRAME_QP,
+        "mpeg_video_mpeg4_p_frame_qp",
+    ),
+    (
+        CID_MPEG_VIDEO_MPEG4_B_FRAME_QP,
+        "mpeg_video_mpeg4_b_frame_qp",
+    ),
+    (CID_MPEG_VIDEO_MPEG4_MIN_QP, "mpeg_video_mpeg4_min_qp"),
+    (CID_MPEG_VIDEO_MPEG4_MAX_QP, "mpeg_video_mpeg4_max_qp"),
+    (CID_MPEG_VIDEO_MPEG4_LEVEL, "mpeg_video_mpeg4_level"),
+    (CID_MPEG_VIDEO_MPEG4_PROFILE, "mpeg_video_mpeg4_profile"),
+    (CID_MPEG_VIDEO_MPEG4_QPEL, "mpeg_video_mpeg4_qpel"),
+    (
+        CID_MPEG_VIDEO_VPX_NUM_PARTITIONS,
+        "mpeg_video_vpx_num_partitions",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_IMD_DISABLE_4X4,
+        "mpeg_video_vpx_imd_disable_4x4",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_NUM_REF_FRAMES,
+        "mpeg_video_vpx_num_ref_frames",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_FILTER_LEVEL,
+        "mpeg_video_vpx_filter_level",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_FILTER_SHARPNESS,
+        "mpeg_video_vpx_filter_sharpness",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_GOLDEN_FRAME_REF_PERIOD,
+        "mpeg_video_vpx_golden_frame_ref_period",
+    ),
+    (
+        CID_MPEG_VIDEO_VPX_GOLDEN_FRAME_SEL,
+        "mpeg_video_vpx_golden_frame_sel",
+    ),
+    (CID_MPEG_VIDEO_VPX_MIN_QP, "mpeg_video_vpx_min_qp"),
+    (CID_MPEG_VIDEO_VPX_MAX_QP, "mpeg_video_vpx_max_qp"),
+    (CID_MPEG_VIDEO_VPX_I_FRAME_QP, "mpeg_video_vpx_i_frame_qp"),
+    (CID_MPEG_VIDEO_VPX_P_FRAME_QP, "mpeg_video_vpx_p_frame_qp"),
+    (CID_MPEG_VIDEO_VPX_PROFILE, "mpeg_video_vpx_profile"),
+    (CID_CAMERA_CLASS, "camera_class"),
+    (CID_EXPOSURE_AUTO, "exposure_auto"),
+    (CID_EXPOSURE_ABSOLUTE, "exposure_absolute"),
+    (CID_EXPOSURE_AUTO_PRIORITY, "exposure_auto_priority"),
+    (CID_FOCUS_AUTO, "focus_auto"),
+    (
+        CID_AUTO_N_PRESET_WHITE_BALANCE,
+        "auto_n_preset_white_balance",
+    ),
+    (CID_EXPOSURE_METERING, "exposure_metering"),
+    (CID_FM_TX_CLASS, "fm_tx_class"),
+    (CID_RDS_TX_DEVIATION, "rds_tx_deviation"),
+    (CID_RDS_TX_PI, "rds_tx_pi"),
+    (CID_RDS_TX_MONO_STEREO, "rds_tx_mono_stereo"),
+    (CID_RDS_TX_ARTIFICIAL_HEAD, "rds_tx_artificial_head"),
+    (CID_RDS_TX_COMPRESSED, "rds_tx_compressed"),
+    (CID_RDS_TX_DYNAMIC_PTY, "rds_tx_dynamic_pty"),
+    (CID_RDS_TX_ALT_FREQS_ENABLE, "rds_tx_alt_freqs_enable"),
+    (CID_RDS_TX_ALT_FREQS, "rds_tx_alt_freqs"),
+    (CID_AUDIO_LIMITER_ENABLED, "audio_limiter_enabled"),
+    (CID_PILOT_TONE_ENABLED, "pilot_tone_enabled"),
+    (CID_TUNE_PREEMPHASIS, "tune_preemphasis"),
+    (CID_FLASH_CLASS, "flash_class"),
+    (CID_FLASH_LED_MODE, "flash_led_mode"),
+    (CID_FLASH_STROBE_SOURCE, "flash_strobe_source"),
+    (CID_FLASH_STROBE, "flash_strobe"),
+    (CID_FLASH_STROBE_STOP, "flash_strobe_stop"),
+    (CID_FLASH_STROBE_STATUS, "flash_strobe_status"),
+    (CID_FLASH_TIMEOUT, "flash_timeout"),
+    (CID_FLASH_INTENSITY, "flash_intensity"),
+    (CID_FLASH_TORCH_INTENSITY, "flash_torch_intensity"),
+    (CID_FLASH_INDICATOR_INTENSITY, "flash_indicator_intensity"),
+    (CID_FLASH_FAULT, "flash_fault"),
+    (CID_FLASH_CHARGE, "flash_charge"),
+    (CID_FLASH_READY, "flash_ready"),
+    (CID_JPEG_CLASS, "jpeg_class"),
+    (CID_JPEG_CHROMA_SUBSAMPLING, "jpeg_chroma_subsampling"),
+    (CID_JPEG_RESTART_INTERVAL, "jpeg_restart_interval"),
+    (CID_JPEG_COMPRESSION_QUALITY, "jpeg_compression_quality"),
+    (CID_JPEG_ACTIVE_MARKER, "jpeg_active_marker"),
+    (CID_IMAGE_SOURCE_CLASS, "image_source_class"),
+    (CID_VBLANK, "vblank"),
+    (CID_HBLANK, "hblank"),
+    (CID_TEST_PATTERN_RED, "test_pattern_red"),
+    (CID_TEST_PATTERN_GREENR, "test_pattern_greenr"),
+    (CID_TEST_PATTERN_BLUE, "test_pattern_blue"),
+    (CID_TEST_PATTERN_GREENB, "test_pattern_greenb"),
+    (CID_IMAGE_PROC_CLASS, "image_proc_class"),
+    (CID_LINK_FREQ, "link_freq"),
+    (CID_DV_CLASS, "dv_class"),
+    (CID_DV_TX_HOTPLUG, "dv_tx_hotplug"),
+    (CID_DV_TX_RXSENSE, "dv_tx_rxsense"),
+    (CID_DV_TX_EDID_PRESENT, "dv_tx_edid_present"),
+    (CID_DV_TX_MODE, "dv_tx_mode"),
+    (CID_DV_TX_RGB_RANGE, "dv_tx_rgb_range"),
+    (CID_DV_RX_POWER_PRESENT, "dv_rx_power_present"),
+    (CID_DV_RX_RGB_RANGE, "dv_rx_rgb_range"),
+    (CID_FM_RX_CLASS, "fm_rx_class"),
+    (CID_TUNE_DEEMPHASIS, "tune_deemphasis"),
+    (CID_RF_TUNER_CLASS, "rf_tuner_class"),
+    (CID_RF_TUNER_BANDWIDTH_AUTO, "rf_tuner_bandwidth_auto"),
+    (CID_RF_TUNER_BANDWIDTH, "rf_tuner_bandwidth"),
+    (CID_RF_TUNER_LNA_GAIN_AUTO, "rf_tuner_lna_gain_auto"),
+    (CID_RF_TUNER_LNA_GAIN, "rf_tuner_lna_gain"),
+    (CID_RF_TUNER_MIXER_GAIN_AUTO, "rf_tuner_mixer_gain_auto"),
+    (CID_RF_TUNER_MIXER_GAIN, "rf_tuner_mixer_gain"),
+    (CID_RF_TUNER_IF_GAIN_AUTO, "rf_tuner_if_gain_auto"),
+    (CID_RF_TUNER_IF_GAIN, "rf_tuner_if_gain"),
+    (CID_RF_TUNER_PLL_LOCK, "rf_tuner_pll_lock"),
+    (CID_DETECT_CLASS, "detect_class"),
+    (CID_DETECT_MD_MODE, "detect_md_mode"),
+    (CID_DETECT_MD_GLOBAL_THRESHOLD, "detect_md_global_threshold"),
+    (CID_DETECT_MD_THRESHOLD_GRID, "detect_md_threshold_grid"),
+    (CID_DETECT_MD_REGION_GRID, "detect_md_region_grid"),
+];
+
+/// Get the canonical name of the standard control,
+/// e.g. `"exposure_time_absolute"` for `CID_EXPOSURE_ABSOLUTE`.
+pub fn control_name(id: u32) -> Option<&'static str> {
+    NAMES.iter().find(|n| n.0 == id).map(|n| n.1)
+}
+
+/// Get the id of the standard control by its canonical name (see `control_name()`)
+/// or by the name of its constant, e.g. `"exposure_absolute"` for `CID_EXPOSURE_ABSOLUTE`.
+pub fn control_id(name: &str) -> Option<u32> {
+    NAMES
+        .iter()
+        .chain(ALIASES.iter())
+        .find(|n| n.1 == name)
+        .map(|n| n.0)
+}
+
+/// Convert the name reported by the driver the same way as v4l2-ctl does,
+/// e.g. `"Exposure Time, Absolute"` to `"exposure_time_absolute"`.
+pub fn normalize_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut underscore = false;
+
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if underscore {
+                result.push('_');
+                underscore = false;
+            }
+
+            result.push(c.to_ascii_lowercase());
+        } else if !result.is_empty() {
+            underscore = true;
+        }
+    }
+
+    result
+}
+
+#[test]
+fn test_names() {
+    assert_eq!(
+        control_name(CID_EXPOSURE_ABSOLUTE),
+        Some("exposure_time_absolute")
+    );
+    assert_eq!(
+        control_name(CID_AUTO_WHITE_BALANCE),
+        Some("white_balance_automatic")
+    );
+    assert_eq!(control_name(CID_WHITENESS), Some("gamma"));
+    assert_eq!(control_name(CID_POWER_LINE_FREQUENCY_DISABLED), None);
+    assert_eq!(control_id("exposure_absolute"), Some(CID_EXPOSURE_ABSOLUTE));
+    assert_eq!(control_id("auto_exposure"), Some(CID_EXPOSURE_AUTO));
+    assert_eq!(control_id("whiteness"), Some(CID_GAMMA));
+    assert_eq!(control_id("brightness"), Some(CID_BRIGHTNESS));
+    assert_eq!(control_id("brightness_base"), None);
+
+    for &(id, name) in NAMES.iter() {
+        assert_eq!(control_id(name), Some(id));
+        assert_eq!(control_name(id), Some(name));
+    }
+
+    for &(id, name) in ALIASES.iter() {
+        assert_eq!(control_id(name), Some(id));
+    }
+
+    assert_eq!(
+        normalize_name("Exposure Time, Absolute"),
+        "exposure_time_absolute"
+    );
+    assert_eq!(
+        normalize_name(" White Balance (Auto) "),
+        "white_balance_auto"
+    );
+}
@@ -0,0 +1,117 @@
+use crate::consts::*;
+use crate::{Control, ControlValue};
+
+/// Values of controls to restore later, see `Camera::snapshot_controls()`.
+///
+/// With feature `serde` it can be stored in a human-editable file (e.g. TOML or JSON).
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlProfile {
+    pub controls: Vec<ControlSetting>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlSetting {
+    pub id: u32,
+    /// Name of the control, only informative.
+    pub name: String,
+    pub value: ControlValue,
+}
+
+/// Controls switching automatic modes, which make other controls inactive or read-only.
+const AUTO_CONTROLS: [u32; 9] = [
+    CID_EXPOSURE_AUTO,
+    CID_EXPOSURE_AUTO_PRIORITY,
+    CID_AUTO_WHITE_BALANCE,
+    CID_AUTO_N_PRESET_WHITE_BALANCE,
+    CID_AUTOGAIN,
+    CID_AUTOBRIGHTNESS,
+    CID_HUE_AUTO,
+    CID_FOCUS_AUTO,
+    CID_ISO_SENSITIVITY_AUTO,
+];
+
+impl ControlSetting {
+    /// Make the setting from the control if it's worth to be stored. Inactive controls are
+    /// skipped, they are driven by automatic modes and drivers can refuse to set them.
+    pub(crate) fn new(ctrl: &Control) -> Option<ControlSetting> {
+        let skipped = FLAG_DISABLED | FLAG_READ_ONLY | FLAG_WRITE_ONLY | FLAG_INACTIVE;
+
+        if ctrl.flags & skipped != 0 {
+            return None;
+        }
+
+        Some(ControlSetting {
+            id: ctrl.id,
+            name: ctrl.name.clone(),
+            value: ctrl.data.value()?,
+        })
+    }
+}
+
+/// Controls which affect others have to be applied first (e.g. auto exposure before exposure).
+pub(crate) fn is_applied_first(id: u32, flags: u32) -> bool {
+    flags & FLAG_UPDATE != 0 || AUTO_CONTROLS.contains(&id)
+}
+
+#[test]
+fn test_profile() {
+    use crate::{CtrlData, CtrlMenuItem, MockDevice};
+
+    let mut device = MockDevice::default();
+
+    device.controls.push(Control {
+        id: CID_EXPOSURE_AUTO,
+        name: "Auto Exposure".into(),
+        data: CtrlData::Menu {
+            value: 3,
+            default: 3,
+            items: vec![
+                CtrlMenuItem {
+                    index: 1,
+                    name: "Manual Mode".into(),
+                },
+                CtrlMenuItem {
+                    index: 3,
+                    name: "Aperture Priority Mode".into(),
+                },
+            ],
+        },
+        flags: FLAG_UPDATE,
+        elem_size: 4,
+        dims: vec![],
+    });
+
+    // Driven by the aperture priority mode.
+    device.controls.push(Control {
+        id: CID_EXPOSURE_ABSOLUTE,
+        name: "Exposure Time, Absolute".into(),
+        data: CtrlData::Integer {
+            value: 156,
+            default: 156,
+            minimum: 3,
+            maximum: 2047,
+            step: 1,
+        },
+        flags: FLAG_INACTIVE,
+        elem_size: 4,
+        dims: vec![],
+    });
+
+    let camera = device.open().unwrap();
+    let profile = camera.snapshot_controls().unwrap();
+
+    assert!(profile
+        .controls
+        .iter()
+        .all(|c| c.id != CID_EXPOSURE_ABSOLUTE));
+
+    camera.set_control(CID_BRIGHTNESS, 10).unwrap();
+    camera
+        .set_control(CID_EXPOSURE_AUTO, ControlValue::Menu(1))
+        .unwrap();
+
+    camera.apply_profile(&profile).unwrap();
+    assert_eq!(camera.snapshot_controls().unwrap(), profile);
+}
@@ -0,0 +1,171 @@
+use std::io;
+
+use crate::{ActualFormat, Camera, Config, Control, ControlValue, Error, FileCamera, FormatInfo};
+use crate::{Frame, IntervalInfo, ResolutionInfo, Result};
+
+/// Source of frames: a real device (`Camera`), recorded footage (`FileCamera`) or
+/// anything else producing frames (see `Frame::new()`), so the code processing frames
+/// doesn't depend on where they come from.
+///
+/// ```no_run
+/// use rscam::{Camera, CaptureSource, Config, FileCamera};
+///
+/// fn grab(source: &mut dyn CaptureSource, format: &[u8; 4]) -> rscam::Result<Vec<u8>> {
+///     let resolution = match source.resolutions(*format)? {
+///         rscam::ResolutionInfo::Discretes(d) => d[0],
+///         rscam::ResolutionInfo::Stepwise { max, .. } => max,
+///     };
+///
+///     source.start(&Config {
+///         interval: (1, 30),
+///         resolution,
+///         format,
+///         ..Default::default()
+///     })?;
+///
+///     let frame = source.capture()?.to_vec();
+///     source.stop()?;
+///     Ok(frame)
+/// }
+///
+/// grab(&mut Camera::new("/dev/video0").unwrap(), b"MJPG").unwrap();
+/// grab(&mut FileCamera::jpeg_dir("footage").unwrap(), b"MJPG").unwrap();
+/// ```
+pub trait CaptureSource {
+    /// Get detailed info about the available formats, see `Camera::formats()`.
+    fn formats(&self) -> Result<Vec<FormatInfo>>;
+
+    /// Get the resolutions of the format, see `Camera::resolutions()`.
+    fn resolutions(&self, format: [u8; 4]) -> Result<ResolutionInfo>;
+
+    /// Get the intervals of the format and the resolution, see `Camera::intervals()`.
+    fn intervals(&self, format: [u8; 4], resolution: (u32, u32)) -> Result<IntervalInfo>;
+
+    /// Get info about all controls, see `Camera::controls()`.
+    fn controls(&self) -> Result<Vec<Control>>;
+
+    /// Set value of the control, see `Camera::set_control()`.
+    fn set_control(&self, id: u32, value: ControlValue) -> Result<()>;
+
+    /// Start streaming, see `Camera::start()`.
+    fn start(&mut self, config: &Config<'_>) -> Result<ActualFormat>;
+
+    /// Blocking request of frame, see `Camera::capture()`.
+    fn capture(&mut self) -> Result<Frame>;
+
+    /// Non-blocking request of frame, see `Camera::try_capture()`.
+    fn try_capture(&mut self) -> Result<Option<Frame>>;
+
+    /// Stop streaming, see `Camera::stop()`.
+    fn stop(&mut self) -> Result<()>;
+}
+
+impl CaptureSource for Camera {
+    fn formats(&self) -> Result<Vec<FormatInfo>> {
+        Camera::formats(self).collect()
+    }
+
+    fn resolutions(&self, format: [u8; 4]) -> Result<ResolutionInfo> {
+        Camera::resolutions(self, format)
+    }
+
+    fn intervals(&self, format: [u8; 4], resolution: (u32, u32)) -> Result<IntervalInfo> {
+        Camera::intervals(self, format, resolution)
+    }
+
+    fn controls(&self) -> Result<Vec<Control>> {
+        Camera::controls(self).collect()
+    }
+
+    fn set_control(&self, id: u32, value: ControlValue) -> Result<()> {
+        Camera::set_control(self, id, value)
+    }
+
+    fn start(&mut self, config: &Config<'_>) -> Result<ActualFormat> {
+        Camera::start(self, config)
+    }
+
+    fn capture(&mut self) -> Result<Frame> {
+        Camera::capture(self)
+    }
+
+    fn try_capture(&mut self) -> Result<Option<Frame>> {
+        Camera::try_capture(self)
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        Camera::stop(self)
+    }
+}
+
+impl CaptureSource for FileCamera {
+    fn formats(&self) -> Result<Vec<FormatInfo>> {
+        Ok(FileCamera::formats(self))
+    }
+
+    fn resolutions(&self, format: [u8; 4]) -> Result<ResolutionInfo> {
+        FileCamera::resolutions(self, format)
+    }
+
+    fn intervals(&self, format: [u8; 4], resolution: (u32, u32)) -> Result<IntervalInfo> {
+        FileCamera::intervals(self, format, resolution)
+    }
+
+    fn controls(&self) -> Result<Vec<Control>> {
+        Ok(vec![])
+    }
+
+    /// There are no controls, so the control is always rejected.
+    fn set_control(&self, id: u32, _value: ControlValue) -> Result<()> {
+        Err(Error::ControlRejected {
+            id,
+            source: io::Error::from_raw_os_error(libc::EINVAL),
+        })
+    }
+
+    fn start(&mut self, config: &Config<'_>) -> Result<ActualFormat> {
+        FileCamera::start(self, config)
+    }
+
+    fn capture(&mut self) -> Result<Frame> {
+        FileCamera::capture(self)
+    }
+
+    fn try_capture(&mut self) -> Result<Option<Frame>> {
+        FileCamera::try_capture(self)
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        FileCamera::stop(self)
+    }
+}
+
+#[test]
+fn test_sources() {
+    use crate::consts::*;
+    use crate::MockDevice;
+
+    let mut camera = MockDevice::default().open().unwrap();
+    let source: &mut dyn CaptureSource = &mut camera;
+
+    assert_eq!(source.formats().unwrap()[1].format, *b"MJPG");
+    assert_eq!(source.controls().unwrap().len(), 3);
+    source
+        .set_control(CID_BRIGHTNESS, ControlValue::Integer(10))
+        .unwrap();
+
+    let format = source
+        .start(&Config {
+            interval: (1, 30),
+            ..Default::default()
+        })
+        .unwrap();
+
+    assert_eq!(source.capture().unwrap().len(), format.sizeimage as usize);
+    source.stop().unwrap();
+
+    let frame = Frame::new(format, vec![1, 2, 3], 10, 5);
+    assert_eq!(&frame[..], &[1, 2, 3]);
+    assert_eq!(frame.resolution, (640, 480));
+    assert_eq!((frame.get_timestamp(), frame.sequence()), (10, 5));
+}
@@ -0,0 +1,771 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::mem;
+use std::os::raw::c_void;
+use std::os::unix::io::RawFd;
+use std::path::Path;
+use std::ptr;
+use std::slice;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::v4l2;
+use crate::{Backend, Kernel};
+
+const MAGIC: &[u8; 8] = b"RSCAMTR\0";
+const VERSION: u32 = 1;
+
+/// Backend, which writes all calls to the underlying one with their results into the trace
+/// (see `Replayer`). The contents of captured frames are written too, so the trace can grow fast.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use rscam::{Camera, Recorder};
+///
+/// let recorder = Recorder::create("camera.trace").unwrap();
+/// let camera = Camera::with_backend(Arc::new(recorder), "/dev/video0").unwrap();
+/// ```
+pub struct Recorder {
+    inner: Arc<dyn Backend>,
+    writer: Mutex<Box<dyn Write + Send>>,
+    /// The first failure to write the trace, nothing is written after it.
+    error: Mutex<Option<io::Error>>,
+    regions: Mutex<Vec<Region>>,
+}
+
+/// Backend, which serves the trace written by `Recorder` instead of the device.
+///
+/// Calls are matched with the trace by their order and kind (e.g. the ioctl request),
+/// their arguments are ignored. Calls beyond the trace or of another kind fail
+/// with `io::ErrorKind::InvalidData`.
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use rscam::{Camera, Replayer};
+///
+/// let replayer = Replayer::open("camera.trace").unwrap();
+/// let camera = Camera::with_backend(Arc::new(replayer), "/dev/video0").unwrap();
+/// ```
+pub struct Replayer {
+    state: Mutex<ReplayState>,
+}
+
+struct ReplayState {
+    records: VecDeque<Record>,
+    position: usize,
+    regions: Vec<Region>,
+    /// Descriptors handed out instead of recorded ones, they're always readable.
+    fds: Vec<RawFd>,
+}
+
+/// Mapped buffer of the device.
+struct Region {
+    fd: RawFd,
+    offset: usize,
+    ptr: usize,
+    length: usize,
+}
+
+enum Record {
+    Open {
+        result: Result<RawFd, i32>,
+    },
+    Close {
+        fd: RawFd,
+        result: Result<(), i32>,
+    },
+    Ioctl {
+        fd: RawFd,
+        request: usize,
+        result: Result<(), i32>,
+        input: Vec<u8>,
+        output: Vec<u8>,
+        /// Memory referenced by the argument: controls and their payloads for
+        /// `VIDIOC_*_EXT_CTRLS`, the contents of the frame for `VIDIOC_DQBUF`.
+        extra: Vec<Vec<u8>>,
+    },
+    Mmap {
+        fd: RawFd,
+        length: usize,
+        offset: usize,
+        result: Result<(), i32>,
+    },
+    Poll {
+        fd: RawFd,
+        events: i16,
+        result: Result<bool, i32>,
+    },
+}
+
+fn errno(err: &io::Error) -> i32 {
+    err.raw_os_error().unwrap_or(libc::EIO)
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn is_ext_controls(request: usize) -> bool {
+    request == v4l2::VIDIOC_G_EXT_CTRLS
+        || request == v4l2::VIDIOC_S_EXT_CTRLS
+        || request == v4l2::VIDIOC_TRY_EXT_CTRLS
+}
+
+unsafe fn read_bytes(ptr: *const u8, len: usize) -> Vec<u8> {
+    if ptr.is_null() {
+        return vec![];
+    }
+
+    slice::from_raw_parts(ptr, len).to_vec()
+}
+
+unsafe fn write_bytes(ptr: *mut u8, len: usize, data: &[u8]) {
+    if !ptr.is_null() {
+        ptr::copy_nonoverlapping(data.as_ptr(), ptr, len.min(data.len()));
+    }
+}
+
+impl Record {
+    fn name(&self) -> String {
+        match *self {
+            Record::Open { .. } => "open()".into(),
+            Record::Close { .. } => "close()".into(),
+            Record::Ioctl { request, .. } => format!("ioctl({})", v4l2::request_name(request)),
+            Record::Mmap { .. } => "mmap()".into(),
+            Record::Poll { .. } => "poll()".into(),
+        }
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        fn put_u32(buf: &mut Vec<u8>, value: u32) {
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn put_u64(buf: &mut Vec<u8>, value: u64) {
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+
+        fn put_bytes(buf: &mut Vec<u8>, data: &[u8]) {
+            put_u32(buf, data.len() as u32);
+            buf.extend_from_slice(data);
+        }
+
+        fn put_errno<T>(buf: &mut Vec<u8>, result: &Result<T, i32>) {
+            put_u32(buf, *result.as_ref().err().unwrap_or(&0) as u32);
+        }
+
+        match *self {
+            Record::Open { ref result } => {
+                buf.push(1);
+                put_errno(buf, result);
+                put_u32(buf, *result.as_ref().unwrap_or(&-1) as u32);
+            }
+            Record::Close { fd, ref result } => {
+                buf.push(2);
+                put_errno(buf, result);
+                put_u32(buf, fd as u32);
+            }
+            Record::Ioctl {
+                fd,
+                request,
+                ref result,
+                ref input,
+                ref output,
+                ref extra,
+            } => {
+                buf.push(3);
+                put_errno(buf, result);
+                put_u32(buf, fd as u32);
+                put_u64(buf, request as u64);
+                put_bytes(buf, input);
+                put_bytes(buf, output);
+                put_u32(buf, extra.len() as u32);
+
+                for data in extra {
+                    put_bytes(buf, data);
+                }
+            }
+            Record::Mmap {
+                fd,
+                length,
+                offset,
+                ref result,
+            } => {
+                buf.push(4);
+                put_errno(buf, result);
+                put_u32(buf, fd as u32);
+                put_u64(buf, length as u64);
+                put_u64(buf, offset as u64);
+            }
+            Record::Poll {
+                fd,
+                events,
+                ref result,
+            } => {
+                buf.push(5);
+                put_errno(buf, result);
+                put_u32(buf, fd as u32);
+                put_u32(buf, events as u16 as u32);
+                put_u32(buf, *result.as_ref().unwrap_or(&false) as u32);
+            }
+        }
+    }
+
+    /// Returns `None` at the end of the trace or if the record is truncated,
+    /// records of unknown kinds are invalid.
+    fn decode(data: &mut &[u8]) -> io::Result<Option<Record>> {
+        match data.first() {
+            Some(&tag) if !(1..=5).contains(&tag) => {
+                Err(invalid_data(format!("unknown kind {} of the record", tag)))
+            }
+            _ => Ok(Record::decode_known(data)),
+        }
+    }
+
+    fn decode_known(data: &mut &[u8]) -> Option<Record> {
+        fn take<'a>(data: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+            if data.len() < len {
+                return None;
+            }
+
+            let (head, tail) = data.split_at(len);
+            *data = tail;
+            Some(head)
+        }
+
+        fn get_u32(data: &mut &[u8]) -> Option<u32> {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(take(data, 4)?);
+            Some(u32::from_le_bytes(bytes))
+        }
+
+        fn get_u64(data: &mut &[u8]) -> Option<u64> {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(take(data, 8)?);
+            Some(u64::from_le_bytes(bytes))
+        }
+
+        fn get_bytes(data: &mut &[u8]) -> Option<Vec<u8>> {
+            let len = get_u32(data)? as usize;
+            Some(take(data, len)?.to_vec())
+        }
+
+        let tag = take(data, 1)?[0];
+        let err = get_u32(data)? as i32;
+        let result = if err == 0 { Ok(()) } else { Err(err) };
+        let fd = get_u32(data)? as RawFd;
+
+        Some(match tag {
+            1 => Record::Open {
+                result: result.map(|_| fd),
+            },
+            2 => Record::Close { fd, result },
+            3 => {
+                let request = get_u64(data)? as usize;
+                let input = get_bytes(data)?;
+                let output = get_bytes(data)?;
+                let count = get_u32(data)?;
+                let extra = (0..count)
+                    .map(|_| get_bytes(data))
+                    .collect::<Option<Vec<_>>>()?;
+
+                Record::Ioctl {
+                    fd,
+                    request,
+                    result,
+                    input,
+                    output,
+                    extra,
+                }
+            }
+            4 => Record::Mmap {
+                fd,
+                length: get_u64(data)? as usize,
+                offset: get_u64(data)? as usize,
+                result,
+            },
+            5 => {
+                let events = get_u32(data)? as u16 as i16;
+                let ready = get_u32(data)? != 0;
+
+                Record::Poll {
+                    fd,
+                    events,
+                    result: result.map(|_| ready),
+                }
+            }
+            _ => unreachable!(),
+        })
+    }
+}
+
+impl Recorder {
+    /// Record calls to the backend into the writer.
+    /// Every call is written at once, so the trace is usable even if the process crashes.
+    ///
+    /// Failures to write the trace don't affect calls, they are reported by `error()`.
+    pub fn new<W: Write + Send + 'static>(
+        inner: Arc<dyn Backend>,
+        mut writer: W,
+    ) -> io::Result<Recorder> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(mem::size_of::<usize>() as u32).to_le_bytes())?;
+
+        Ok(Recorder {
+            inner,
+            writer: Mutex::new(Box::new(writer)),
+            error: Mutex::new(None),
+            regions: Mutex::new(vec![]),
+        })
+    }
+
+    /// Record calls to the kernel into the file.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Recorder> {
+        Recorder::new(Arc::new(Kernel), File::create(path)?)
+    }
+
+    /// Get the failure to write the trace, the trace is incomplete then.
+    pub fn error(&self) -> Option<io::Error> {
+        self.error
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|err| match err.raw_os_error() {
+                Some(code) => io::Error::from_raw_os_error(code),
+                None => io::Error::new(err.kind(), err.to_string()),
+            })
+    }
+
+    fn write(&self, record: &Record) {
+        let mut error = self.error.lock().unwrap();
+
+        if error.is_some() {
+            return;
+        }
+
+        let mut buf = vec![];
+        record.encode(&mut buf);
+
+        if let Err(err) = self.writer.lock().unwrap().write_all(&buf) {
+            *error = Some(err);
+        }
+    }
+
+    /// The contents of the frame dequeued by `VIDIOC_DQBUF`.
+    unsafe fn frame(&self, fd: RawFd, arg: *mut c_void) -> Vec<u8> {
+        let buf = &*(arg as *const v4l2::Buffer);
+        let regions = self.regions.lock().unwrap();
+
+        match regions.iter().find(|r| r.fd == fd && r.offset == buf.m) {
+            Some(region) => read_bytes(
+                region.ptr as *const u8,
+                region.length.min(buf.bytesused as usize),
+            ),
+            None => vec![],
+        }
+    }
+}
+
+impl Backend for Recorder {
+    fn open(&self, path: &str) -> io::Result<RawFd> {
+        let result = self.inner.open(path);
+
+        self.write(&Record::Open {
+            result: result.as_ref().map(|&fd| fd).map_err(errno),
+        });
+
+        result
+    }
+
+    fn close(&self, fd: RawFd) -> io::Result<()> {
+        let result = self.inner.close(fd);
+
+        self.write(&Record::Close {
+            fd,
+            result: result.as_ref().map(|_| ()).map_err(errno),
+        });
+
+        result
+    }
+
+    unsafe fn ioctl(&self, fd: RawFd, request: usize, arg: *mut c_void) -> io::Result<()> {
+        let size = v4l2::request_size(request);
+        let input = read_bytes(arg as *const u8, size);
+
+        // Sizes and pointers of payloads, the driver can change sizes (e.g. on `ENOSPC`).
+        let payloads = if is_ext_controls(request) {
+            let ext = &mut *(arg as *mut v4l2::ExtControls<'_>);
+            ext.controls()
+                .iter()
+                .map(|c| (c.size as usize, c.value as usize))
+                .collect()
+        } else {
+            vec![]
+        };
+
+        let result = self.inner.ioctl(fd, request, arg);
+        let output = read_bytes(arg as *const u8, size);
+        let mut extra = vec![];
+
+        if is_ext_controls(request) {
+            let ext = &mut *(arg as *mut v4l2::ExtControls<'_>);
+            let controls = ext.controls();
+            let len = mem::size_of_val(controls);
+            extra.push(read_bytes(controls.as_ptr() as *const u8, len));
+
+            for &(size, ptr) in &payloads {
+                extra.push(if size > 0 {
+                    read_bytes(ptr as *const u8, size)
+                } else {
+                    vec![]
+                });
+            }
+        }
+
+        if request == v4l2::VIDIOC_DQBUF && result.is_ok() {
+            extra.push(self.frame(fd, arg));
+        }
+
+        self.write(&Record::Ioctl {
+            fd,
+            request,
+            result: result.as_ref().map(|_| ()).map_err(errno),
+            input,
+            output,
+            extra,
+        });
+
+        result
+    }
+
+    fn mmap(&self, fd: RawFd, length: usize, offset: usize) -> io::Result<*mut u8> {
+        let result = self.inner.mmap(fd, length, offset);
+
+        if let Ok(ptr) = result {
+            self.regions.lock().unwrap().push(Region {
+                fd,
+                offset,
+                ptr: ptr as usize,
+                length,
+            });
+        }
+
+        self.write(&Record::Mmap {
+            fd,
+            length,
+            offset,
+            result: result.as_ref().map(|_| ()).map_err(errno),
+        });
+
+        result
+    }
+
+    unsafe fn munmap(&self, ptr: *mut u8, length: usize) {
+        self.regions
+            .lock()
+            .unwrap()
+            .retain(|r| r.ptr != ptr as usize);
+
+        self.inner.munmap(ptr, length);
+    }
+
+    fn poll(&self, fd: RawFd, events: i16, timeout: Option<Duration>) -> io::Result<bool> {
+        let result = self.inner.poll(fd, events, timeout);
+
+        self.write(&Record::Poll {
+            fd,
+            events,
+            result: result.as_ref().map(|&ready| ready).map_err(errno),
+        });
+
+        result
+    }
+}
+
+impl Replayer {
+    /// Read the whole trace. A truncated record at the end of the trace is ignored.
+    pub fn new<R: Read>(mut reader: R) -> io::Result<Replayer> {
+        let mut data = vec![];
+        reader.read_to_end(&mut data)?;
+
+        if data.len() < 16 || &data[..8] != MAGIC {
+            return Err(invalid_data("not a trace of rscam".into()));
+        }
+
+        if data[8..12] != VERSION.to_le_bytes() {
+            return Err(invalid_data("unsupported version of the trace".into()));
+        }
+
+        if data[12..16] != (mem::size_of::<usize>() as u32).to_le_bytes() {
+            return Err(invalid_data(
+                "the trace is recorded on another architecture".into(),
+            ));
+        }
+
+        let mut rest = &data[16..];
+        let mut records = VecDeque::new();
+
+        while let Some(record) = Record::decode(&mut rest)? {
+            records.push_back(record);
+        }
+
+        Ok(Replayer {
+            state: Mutex::new(ReplayState {
+                records,
+                position: 0,
+                regions: vec![],
+                fds: vec![],
+            }),
+        })
+    }
+
+    /// Read the trace from the file.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Replayer> {
+        Replayer::new(File::open(path)?)
+    }
+}
+
+impl ReplayState {
+    /// Take the next record if it's of the expected kind.
+    fn next(&mut self, expected: &str) -> io::Result<Record> {
+        let position = self.position;
+
+        let record = self.records.pop_front().ok_or_else(|| {
+            invalid_data(format!(
+                "the trace is over at record {}, but {} is called",
+                position, expected
+            ))
+        })?;
+
+        if record.name() != expected {
+            let found = record.name();
+            self.records.push_front(record);
+
+            return Err(invalid_data(format!(
+                "the trace diverges at record {}: {} is called instead of {}",
+                position, expected, found
+            )));
+        }
+
+        self.position += 1;
+        Ok(record)
+    }
+}
+
+impl Backend for Replayer {
+    fn open(&self, _path: &str) -> io::Result<RawFd> {
+        let mut state = self.state.lock().unwrap();
+
+        match state.next("open()")? {
+            Record::Open { result } => result.map_err(io::Error::from_raw_os_error)?,
+            _ => unreachable!(),
+        };
+
+        // The recorded descriptor can't be exposed, it may belong to anything in this process.
+        let fd = v4l2::eventfd()?;
+        v4l2::set_ready(fd, true);
+        state.fds.push(fd);
+
+        Ok(fd)
+    }
+
+    fn close(&self, fd: RawFd) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(index) = state.fds.iter().position(|&f| f == fd) {
+            v4l2::close_eventfd(state.fds.remove(index));
+        }
+
+        match state.next("close()")? {
+            Record::Close { result, .. } => result.map_err(io::Error::from_raw_os_error),
+            _ => unreachable!(),
+        }
+    }
+
+    unsafe fn ioctl(&self, fd: RawFd, request: usize, arg: *mut c_void) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let expected = format!("ioctl({})", v4l2::request_name(request));
+
+        let (result, output, extra) = match state.next(&expected)? {
+            Record::Ioctl {
+                result,
+                output,
+                extra,
+                ..
+            } => (result, output, extra),
+            _ => unreachable!(),
+        };
+
+        let size = v4l2::request_size(request);
+
+        if is_ext_controls(request) {
+            // Pointers of the caller must be kept, so only values are copied.
+            let ext = &mut *(arg as *mut v4l2::ExtControls<'_>);
+
+            if output.len() >= mem::size_of::<v4l2::ExtControls<'_>>() {
+                let recorded = ptr::read_unaligned(output.as_ptr() as *const v4l2::ExtControls<'_>);
+                ext.error_idx = recorded.error_idx;
+            }
+
+            let controls = extra.first().map(|c| &c[..]).unwrap_or(&[]);
+            let elem = mem::size_of::<v4l2::ExtControl>();
+
+            for (i, ctrl) in ext.controls().iter_mut().enumerate() {
+                if controls.len() < (i + 1) * elem {
+                    break;
+                }
+
+                let recorded =
+                    ptr::read_unaligned(controls[i * elem..].as_ptr() as *const v4l2::ExtControl);
+
+                if ctrl.size > 0 {
+                    let payload = extra.get(i + 1).map(|p| &p[..]).unwrap_or(&[]);
+                    write_bytes(ctrl.value as usize as *mut u8, ctrl.size as usize, payload);
+                } else {
+                    ctrl.value = recorded.value;
+                }
+
+                ctrl.size = recorded.size;
+            }
+        } else {
+            write_bytes(arg as *mut u8, size, &output);
+        }
+
+        if request == v4l2::VIDIOC_DQBUF && result.is_ok() {
+            let buf = &*(arg as *const v4l2::Buffer);
+            let frame = extra.first().map(|f| &f[..]).unwrap_or(&[]);
+
+            if let Some(region) = state
+                .regions
+                .iter()
+                .find(|r| r.fd == fd && r.offset == buf.m)
+            {
+                write_bytes(region.ptr as *mut u8, region.length, frame);
+            }
+        }
+
+        result.map_err(io::Error::from_raw_os_error)
+    }
+
+    fn mmap(&self, fd: RawFd, length: usize, offset: usize) -> io::Result<*mut u8> {
+        let mut state = self.state.lock().unwrap();
+
+        match state.next("mmap()")? {
+            Record::Mmap { result, .. } => result.map_err(io::Error::from_raw_os_error)?,
+            _ => unreachable!(),
+        }
+
+        // Freed by `munmap()`.
+        let ptr = Box::into_raw(vec![0u8; length].into_boxed_slice()) as *mut u8;
+
+        state.regions.push(Region {
+            fd,
+            offset,
+            ptr: ptr as usize,
+            length,
+        });
+
+        Ok(ptr)
+    }
+
+    unsafe fn munmap(&self, ptr: *mut u8, length: usize) {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(index) = state.regions.iter().position(|r| r.ptr == ptr as usize) {
+            state.regions.remove(index);
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, length)));
+        }
+    }
+
+    fn poll(&self, _fd: RawFd, _events: i16, _timeout: Option<Duration>) -> io::Result<bool> {
+        match self.state.lock().unwrap().next("poll()")? {
+            Record::Poll { result, .. } => result.map_err(io::Error::from_raw_os_error),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Drop for Replayer {
+    fn drop(&mut self) {
+        for &fd in &self.state.get_mut().unwrap().fds {
+            v4l2::close_eventfd(fd);
+        }
+    }
+}
+
+#[test]
+fn test_replay() {
+    use crate::consts::*;
+    use crate::{Camera, Config, ControlValue, Error, MockDevice};
+
+    let path = std::env::temp_dir().join(format!("rscam-{}.trace", std::process::id()));
+
+    let session = |camera: &mut Camera| {
+        camera
+            .start(&Config {
+                interval: (1, 30),
+                ..Default::default()
+            })
+            .unwrap();
+        let frames = (0..3)
+            .map(|_| {
+                let frame = camera.capture().unwrap();
+                (frame.get_timestamp(), frame.to_vec())
+            })
+            .collect::<Vec<_>>();
+        camera.stop().unwrap();
+
+        camera.set_control(CID_BRIGHTNESS, 42).unwrap();
+        let control = camera.get_control(CID_BRIGHTNESS).unwrap();
+        (frames, control.data.value())
+    };
+
+    let recorder = Recorder::new(
+        MockDevice::default().into_backend(),
+        File::create(&path).unwrap(),
+    )
+    .unwrap();
+
+    let mut camera = Camera::with_backend(Arc::new(recorder), "mock").unwrap();
+    let (frames, data) = session(&mut camera);
+    drop(camera);
+
+    assert_eq!(frames[2].1[0], 2);
+
+    assert_eq!(data, Some(ControlValue::Integer(42)));
+
+    let replayer = Replayer::open(&path).unwrap();
+    let mut camera = Camera::with_backend(Arc::new(replayer), "/dev/video0").unwrap();
+    assert_eq!(session(&mut camera), (frames, data));
+
+    // The trace is over.
+    match camera.capabilities() {
+        Err(Error::Ioctl { ref source, .. }) => {
+            assert_eq!(source.kind(), io::ErrorKind::InvalidData)
+        }
+        res => panic!("unexpected {:?}", res.map(|_| ())),
+    }
+
+    drop(camera);
+    std::fs::remove_file(&path).unwrap();
+
+    // There is only room for the header.
+    let writer = io::Cursor::new(vec![0; 16].into_boxed_slice());
+    let recorder = Arc::new(Recorder::new(MockDevice::default().into_backend(), writer).unwrap());
+    let camera = Camera::with_backend(recorder.clone(), "mock").unwrap();
+
+    assert!(camera.capabilities().is_ok());
+    assert_eq!(recorder.error().unwrap().kind(), io::ErrorKind::WriteZero);
+
+    let mut trace = MAGIC.to_vec();
+    trace.extend(&VERSION.to_le_bytes());
+    trace.extend(&(mem::size_of::<usize>() as u32).to_le_bytes());
+    trace.push(42);
+
+    match Replayer::new(&trace[..]) {
+        Err(err) => assert_eq!(err.kind(), io::ErrorKind::InvalidData),
+        Ok(_) => panic!("unknown record is accepted"),
+    }
+}
@@ -1,14 +1,23 @@
 #![allow(clippy::unreadable_literal)]
 
 use std::ffi::CString;
+use std::marker::PhantomData;
 use std::os::unix::io::RawFd;
 use std::ptr::null_mut;
-use std::{io, mem, usize};
+use std::sync::Arc;
+use std::time::Duration;
+use std::{io, mem, slice};
+
+use crate::backend::Backend;
 
 // C types and constants.
+use libc::timespec as Timespec;
 use libc::timeval as Timeval;
-use libc::{c_ulong, c_void, off_t, size_t};
-use libc::{MAP_SHARED, O_RDWR, PROT_READ, PROT_WRITE};
+use libc::{c_int, c_short, c_ulong, c_void, off_t, pollfd, size_t};
+use libc::{MAP_SHARED, O_NONBLOCK, O_RDWR, PROT_READ, PROT_WRITE};
+
+// Conditions to poll: a buffer can be dequeued or an event is pending.
+pub use libc::{POLLIN, POLLPRI};
 
 #[cfg(not(feature = "no_wrapper"))]
 mod ll {
@@ -16,6 +25,7 @@ mod ll {
     use std::os::unix::io::RawFd;
 
     pub use self::v4l2_close as close;
+    pub use self::v4l2_fd_open as fd_open;
     pub use self::v4l2_ioctl as ioctl;
     pub use self::v4l2_mmap as mmap;
     pub use self::v4l2_munmap as munmap;
@@ -24,6 +34,7 @@ mod ll {
     #[link(name = "v4l2")]
     extern "C" {
         pub fn v4l2_open(file: *const c_char, flags: c_int, arg: c_int) -> RawFd;
+        pub fn v4l2_fd_open(fd: RawFd, v4l2_flags: c_int) -> RawFd;
         pub fn v4l2_close(fd: RawFd) -> c_int;
         pub fn v4l2_ioctl(fd: RawFd, request: c_ulong, argp: *mut c_void) -> c_int;
         pub fn v4l2_mmap(
@@ -48,6 +59,10 @@ mod ll {
     extern "C" {
         pub fn ioctl(fd: RawFd, request: c_ulong, argp: *mut c_void) -> c_int;
     }
+
+    pub unsafe fn fd_open(fd: RawFd, _v4l2_flags: c_int) -> RawFd {
+        fd
+    }
 }
 
 macro_rules! check_io(
@@ -57,24 +72,62 @@ macro_rules! check_io(
 
 pub fn open(file: &str) -> io::Result<RawFd> {
     let c_str = CString::new(file)?;
-    let fd = unsafe { ll::open(c_str.as_ptr(), O_RDWR, 0) };
+    let fd = unsafe { ll::open(c_str.as_ptr(), O_RDWR | O_NONBLOCK, 0) };
     check_io!(fd != -1);
     Ok(fd)
 }
 
+/// Adopt the already opened descriptor, switching it to non-blocking mode.
+pub fn fd_open(fd: RawFd) -> RawFd {
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags != -1 {
+            libc::fcntl(fd, libc::F_SETFL, flags | O_NONBLOCK);
+        }
+
+        // libv4l2 passes unknown descriptors through, so failure isn't fatal.
+        ll::fd_open(fd, 0);
+    }
+
+    fd
+}
+
 pub fn close(fd: RawFd) -> io::Result<()> {
     check_io!(unsafe { ll::close(fd) != -1 });
     Ok(())
 }
 
-pub fn xioctl<T>(fd: RawFd, request: usize, arg: &mut T) -> io::Result<()> {
-    let argp: *mut T = arg;
+/// Descriptor for backends without a device, it's readable after `set_ready(fd, true)`.
+pub fn eventfd() -> io::Result<RawFd> {
+    let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+    check_io!(fd != -1);
+    Ok(fd)
+}
 
-    check_io!(unsafe {
+pub fn set_ready(fd: RawFd, ready: bool) {
+    let mut counter = 1u64;
+    let ptr = &mut counter as *mut u64 as *mut c_void;
+
+    // Failures only mean that the counter is already set or already cleared.
+    unsafe {
+        if ready {
+            libc::write(fd, ptr, mem::size_of::<u64>());
+        } else {
+            libc::read(fd, ptr, mem::size_of::<u64>());
+        }
+    }
+}
+
+pub fn close_eventfd(fd: RawFd) {
+    unsafe { libc::close(fd) };
+}
+
+pub unsafe fn ioctl(fd: RawFd, request: usize, argp: *mut c_void) -> io::Result<()> {
+    check_io!({
         let mut ok;
 
         loop {
-            ok = ll::ioctl(fd, request as c_ulong, argp as *mut c_void) != -1;
+            ok = ll::ioctl(fd, request as c_ulong, argp) != -1;
             if ok || io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
                 break;
             }
@@ -86,32 +139,95 @@ pub fn xioctl<T>(fd: RawFd, request: usize, arg: &mut T) -> io::Result<()> {
     Ok(())
 }
 
-pub fn xioctl_valid<T>(fd: RawFd, request: usize, arg: &mut T) -> io::Result<bool> {
-    match xioctl(fd, request, arg) {
+pub fn xioctl<T>(backend: &dyn Backend, fd: RawFd, request: usize, arg: &mut T) -> io::Result<()> {
+    let argp: *mut T = arg;
+    unsafe { backend.ioctl(fd, request, argp as *mut c_void) }
+}
+
+pub fn xioctl_valid<T>(
+    backend: &dyn Backend,
+    fd: RawFd,
+    request: usize,
+    arg: &mut T,
+) -> io::Result<bool> {
+    match xioctl(backend, fd, request, arg) {
         Ok(_) => Ok(true),
         Err(ref err) if err.kind() == io::ErrorKind::InvalidInput => Ok(false),
         Err(err) => Err(err),
     }
 }
 
+/// Convert the timeout to milliseconds for `poll(2)`, rounding up, so short timeouts still block.
+fn poll_timeout(timeout: Option<Duration>) -> c_int {
+    match timeout {
+        Some(timeout) => {
+            let millis = timeout.as_nanos().div_ceil(1_000_000);
+            millis.min(c_int::MAX as u128) as c_int
+        }
+        None => -1,
+    }
+}
+
+/// Wait until a buffer can be dequeued (`POLLIN`) or an event is pending (`POLLPRI`).
+/// Returns `false` on timeout.
+pub fn poll(fd: RawFd, events: c_short, timeout: Option<Duration>) -> io::Result<bool> {
+    poll_fds(
+        &mut [pollfd {
+            fd,
+            events,
+            revents: 0,
+        }],
+        timeout,
+    )
+}
+
+/// Wait until one of the descriptors is ready. Returns `false` on timeout.
+pub fn poll_fds(fds: &mut [pollfd], timeout: Option<Duration>) -> io::Result<bool> {
+    let timeout = poll_timeout(timeout);
+
+    loop {
+        match unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout) } {
+            -1 if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted => continue,
+            -1 => return Err(io::Error::last_os_error()),
+            n => return Ok(n > 0),
+        }
+    }
+}
+
 pub struct MappedRegion {
     pub ptr: *mut u8,
     pub len: usize,
+    backend: Arc<dyn Backend>,
 }
 
 // Instead of unstable `Unique<u8>`.
 unsafe impl Send for MappedRegion {}
 unsafe impl Sync for MappedRegion {}
 
+impl MappedRegion {
+    pub fn new(
+        backend: Arc<dyn Backend>,
+        fd: RawFd,
+        length: usize,
+        offset: usize,
+    ) -> io::Result<MappedRegion> {
+        Ok(MappedRegion {
+            ptr: backend.mmap(fd, length, offset)?,
+            len: length,
+            backend,
+        })
+    }
+}
+
 impl Drop for MappedRegion {
     fn drop(&mut self) {
         unsafe {
-            ll::munmap(self.ptr as *mut c_void, self.len as size_t);
+            self.backend.munmap(self.ptr, self.len);
         }
     }
 }
 
-pub fn mmap(length: usize, fd: RawFd, offset: usize) -> io::Result<MappedRegion> {
+pub fn mmap(length: usize, fd: RawFd, offset: usize) -> io::Result<*mut u8> {
     let ptr = unsafe {
         ll::mmap(
             null_mut(),
@@ -124,10 +240,28 @@ pub fn mmap(length: usize, fd: RawFd, offset: usize) -> io::Result<MappedRegion>
     };
 
     check_io!(ptr as usize != usize::MAX);
-    Ok(MappedRegion {
-        ptr: ptr as *mut u8,
-        len: length,
-    })
+    Ok(ptr as *mut u8)
+}
+
+pub unsafe fn munmap(ptr: *mut u8, length: usize) {
+    ll::munmap(ptr as *mut c_void, length as size_t);
+}
+
+#[repr(C)]
+pub struct Capability {
+    pub driver: [u8; 16],
+    pub card: [u8; 32],
+    pub bus_info: [u8; 32],
+    pub version: u32,
+    pub capabilities: u32,
+    pub device_caps: u32,
+    reserved: [u32; 3],
+}
+
+impl Capability {
+    pub fn new() -> Capability {
+        unsafe { mem::zeroed() }
+    }
 }
 
 #[repr(C)]
@@ -450,6 +584,19 @@ impl QueryMenuData {
     }
 }
 
+#[cfg(any(test, feature = "mock"))]
+impl QueryMenuData {
+    pub fn set_name(&mut self, name: &[u8]) {
+        let mut buf = [0; 32];
+        copy_name(&mut buf, name);
+        self.name = buf;
+    }
+
+    pub fn set_value(&mut self, value: i64) {
+        self.value = value;
+    }
+}
+
 #[repr(C)]
 pub struct Control {
     pub id: u32,
@@ -487,17 +634,127 @@ pub struct ExtControls<'a> {
     pub count: u32,
     pub error_idx: u32,
     reserved: [u32; 2],
-    pub controls: &'a mut ExtControl,
+    controls: *mut ExtControl,
+    marker: PhantomData<&'a mut [ExtControl]>,
 }
 
 impl<'a> ExtControls<'a> {
-    pub fn new(class: u32, ctrl: &mut ExtControl) -> ExtControls<'_> {
+    pub fn new(class: u32, ctrls: &'a mut [ExtControl]) -> ExtControls<'a> {
         ExtControls {
             ctrl_class: class,
-            count: 1,
-            error_idx: 0,
+            count: ctrls.len() as u32,
+            // Untouched by failures unrelated to controls (e.g. `ENODEV`).
+            error_idx: ctrls.len() as u32,
             reserved: [0; 2],
-            controls: ctrl,
+            controls: ctrls.as_mut_ptr(),
+            marker: PhantomData,
+        }
+    }
+
+    /// # Safety
+    /// The controls must be passed by the caller of the request.
+    pub unsafe fn controls(&mut self) -> &mut [ExtControl] {
+        slice::from_raw_parts_mut(self.controls, self.count as usize)
+    }
+}
+
+/// Copy the name to the fixed-size buffer, leaving the terminating NUL.
+#[cfg(any(test, feature = "mock"))]
+pub fn copy_name(dst: &mut [u8], name: &[u8]) {
+    let len = name.len().min(dst.len().saturating_sub(1));
+    dst[..len].copy_from_slice(&name[..len]);
+}
+
+#[repr(C)]
+pub struct EventSubscription {
+    pub etype: u32,
+    pub id: u32,
+    pub flags: u32,
+    reserved: [u32; 5],
+}
+
+impl EventSubscription {
+    pub fn new(etype: u32, id: u32) -> EventSubscription {
+        EventSubscription {
+            etype,
+            id,
+            flags: 0,
+            reserved: [0; 5],
+        }
+    }
+}
+
+#[repr(C)]
+pub struct Event {
+    pub etype: u32,
+    pub u: EventData,
+    pub pending: u32,
+    pub sequence: u32,
+    pub timestamp: Timespec,
+    pub id: u32,
+    reserved: [u32; 8],
+}
+
+impl Event {
+    pub fn new() -> Event {
+        unsafe { mem::zeroed() }
+    }
+}
+
+#[repr(C)]
+pub union EventData {
+    pub vsync: EventVsync,
+    pub ctrl: EventCtrl,
+    pub frame_sync: EventFrameSync,
+    pub src_change: EventSrcChange,
+    data: [u8; 64],
+}
+
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+pub struct EventVsync {
+    pub field: u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct EventCtrl {
+    pub changes: u32,
+    pub ctype: u32,
+    pub value: EventCtrlValue,
+    pub flags: u32,
+    pub minimum: i32,
+    pub maximum: i32,
+    pub step: i32,
+    pub default_value: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union EventCtrlValue {
+    pub value: i32,
+    pub value64: i64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct EventFrameSync {
+    pub frame_sequence: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct EventSrcChange {
+    pub changes: u32,
+}
+
+impl EventCtrl {
+    pub fn value(&self) -> i64 {
+        unsafe {
+            match self.ctype {
+                CTRL_TYPE_INTEGER64 => self.value.value64,
+                _ => i64::from(self.value.value),
+            }
         }
     }
 }
@@ -506,11 +763,26 @@ pub const BUF_TYPE_VIDEO_CAPTURE: u32 = 1;
 pub const FMT_FLAG_COMPRESSED: u32 = 1;
 pub const FMT_FLAG_EMULATED: u32 = 2;
 pub const FRMIVAL_TYPE_DISCRETE: u32 = 1;
+#[cfg(any(test, feature = "mock"))]
+pub const FRMIVAL_TYPE_STEPWISE: u32 = 3;
 pub const FRMSIZE_TYPE_DISCRETE: u32 = 1;
+#[cfg(any(test, feature = "mock"))]
+pub const FRMSIZE_TYPE_STEPWISE: u32 = 3;
+#[cfg(any(test, feature = "mock"))]
+pub const CAP_TIMEPERFRAME: u32 = 0x1000;
 pub const MEMORY_MMAP: u32 = 1;
 
 pub const ID2CLASS: u32 = 0x0fff0000;
+pub const CTRL_WHICH_CUR_VAL: u32 = 0;
 pub const NEXT_CTRL: u32 = 0x80000000;
+pub const NEXT_COMPOUND: u32 = 0x40000000;
+
+// Event types.
+pub const EVENT_VSYNC: u32 = 1;
+pub const EVENT_EOS: u32 = 2;
+pub const EVENT_CTRL: u32 = 3;
+pub const EVENT_FRAME_SYNC: u32 = 4;
+pub const EVENT_SOURCE_CHANGE: u32 = 5;
 
 // Control types.
 pub const CTRL_TYPE_INTEGER: u32 = 1;
@@ -522,6 +794,10 @@ pub const CTRL_TYPE_CTRL_CLASS: u32 = 6;
 pub const CTRL_TYPE_STRING: u32 = 7;
 pub const CTRL_TYPE_BITMASK: u32 = 8;
 pub const CTRL_TYPE_INTEGER_MENU: u32 = 9;
+pub const CTRL_TYPE_U8: u32 = 0x0100;
+pub const CTRL_TYPE_U16: u32 = 0x0101;
+pub const CTRL_TYPE_U32: u32 = 0x0102;
+pub const CTRL_TYPE_AREA: u32 = 0x0106;
 
 #[allow(non_upper_case_globals)]
 pub mod pubconsts {
@@ -572,6 +848,78 @@ pub mod pubconsts {
     /// For example: clearing an error flag or triggering the flash.
     pub const FLAG_EXECUTE_ON_WRITE: u32 = 0x0200;
 
+    // Changes reported by control events.
+    /// The value of the control has changed.
+    pub const EVENT_CTRL_CH_VALUE: u32 = 0x0001;
+    /// The flags of the control have changed.
+    pub const EVENT_CTRL_CH_FLAGS: u32 = 0x0002;
+    /// The minimum, maximum, step or default value of the control has changed.
+    pub const EVENT_CTRL_CH_RANGE: u32 = 0x0004;
+
+    // Changes reported by source change events.
+    /// The resolution of the source has changed, the format has to be negotiated again.
+    pub const EVENT_SRC_CH_RESOLUTION: u32 = 0x0001;
+
+    // Capabilities.
+    /// The device supports the single-planar API through the Video Capture interface.
+    pub const CAP_VIDEO_CAPTURE: u32 = 0x00000001;
+    /// The device supports the single-planar API through the Video Output interface.
+    pub const CAP_VIDEO_OUTPUT: u32 = 0x00000002;
+    /// The device supports the Video Overlay interface.
+    pub const CAP_VIDEO_OVERLAY: u32 = 0x00000004;
+    /// The device supports the Raw VBI Capture interface.
+    pub const CAP_VBI_CAPTURE: u32 = 0x00000010;
+    /// The device supports the Raw VBI Output interface.
+    pub const CAP_VBI_OUTPUT: u32 = 0x00000020;
+    /// The device supports the Sliced VBI Capture interface.
+    pub const CAP_SLICED_VBI_CAPTURE: u32 = 0x00000040;
+    /// The device supports the Sliced VBI Output interface.
+    pub const CAP_SLICED_VBI_OUTPUT: u32 = 0x00000080;
+    /// The device supports the RDS capture interface.
+    pub const CAP_RDS_CAPTURE: u32 = 0x00000100;
+    /// The device supports the Video Output Overlay (OSD) interface.
+    pub const CAP_VIDEO_OUTPUT_OVERLAY: u32 = 0x00000200;
+    /// The device supports the hardware frequency seeking.
+    pub const CAP_HW_FREQ_SEEK: u32 = 0x00000400;
+    /// The device supports the RDS output interface.
+    pub const CAP_RDS_OUTPUT: u32 = 0x00000800;
+    /// The device supports the multi-planar API through the Video Capture interface.
+    pub const CAP_VIDEO_CAPTURE_MPLANE: u32 = 0x00001000;
+    /// The device supports the multi-planar API through the Video Output interface.
+    pub const CAP_VIDEO_OUTPUT_MPLANE: u32 = 0x00002000;
+    /// The device supports the multi-planar API through the Video Memory-To-Memory interface.
+    pub const CAP_VIDEO_M2M_MPLANE: u32 = 0x00004000;
+    /// The device supports the single-planar API through the Video Memory-To-Memory interface.
+    pub const CAP_VIDEO_M2M: u32 = 0x00008000;
+    /// The device has some sort of tuner to receive RF-modulated video signals.
+    pub const CAP_TUNER: u32 = 0x00010000;
+    /// The device has audio inputs or outputs.
+    pub const CAP_AUDIO: u32 = 0x00020000;
+    /// This is a radio receiver.
+    pub const CAP_RADIO: u32 = 0x00040000;
+    /// The device has some sort of modulator to emit RF-modulated video/audio signals.
+    pub const CAP_MODULATOR: u32 = 0x00080000;
+    /// The device supports the SDR Capture interface.
+    pub const CAP_SDR_CAPTURE: u32 = 0x00100000;
+    /// The device supports the extended fields of the pixel format.
+    pub const CAP_EXT_PIX_FORMAT: u32 = 0x00200000;
+    /// The device supports the SDR Output interface.
+    pub const CAP_SDR_OUTPUT: u32 = 0x00400000;
+    /// The device supports the Metadata Capture interface.
+    pub const CAP_META_CAPTURE: u32 = 0x00800000;
+    /// The device supports the `read()` and/or `write()` I/O methods.
+    pub const CAP_READWRITE: u32 = 0x01000000;
+    /// The device supports the streaming I/O method.
+    pub const CAP_STREAMING: u32 = 0x04000000;
+    /// The device supports the Metadata Output interface.
+    pub const CAP_META_OUTPUT: u32 = 0x08000000;
+    /// This is a touch device.
+    pub const CAP_TOUCH: u32 = 0x10000000;
+    /// The device is a Media Controller centric device.
+    pub const CAP_IO_MC: u32 = 0x20000000;
+    /// The driver fills the `device_caps` field.
+    pub const CAP_DEVICE_CAPS: u32 = 0x80000000;
+
     // Control classses.
     /// User controls.
     /// [Details](http://linuxtv.org/downloads/v4l-dvb-apis/uapi/v4l/control.html).
@@ -1271,6 +1619,10 @@ pub mod pubconsts {
 }
 
 // IOCTL codes.
+#[cfg(target_os = "linux")]
+pub const VIDIOC_QUERYCAP: usize = 2154321408;
+#[cfg(target_os = "freebsd")]
+pub const VIDIOC_QUERYCAP: usize = 1080579584;
 pub const VIDIOC_ENUM_FMT: usize = 3225441794;
 pub const VIDIOC_ENUM_FRAMEINTERVALS: usize = 3224655435;
 pub const VIDIOC_ENUM_FRAMESIZES: usize = 3224131146;
@@ -1304,11 +1656,21 @@ pub const VIDIOC_QUERYBUF: usize = 3227014665;
 #[cfg(target_pointer_width = "32")]
 pub const VIDIOC_QUERYBUF: usize = 3225703945;
 
+#[cfg(target_pointer_width = "64")]
+pub const VIDIOC_G_FMT: usize = 3234878980;
+#[cfg(target_pointer_width = "32")]
+pub const VIDIOC_G_FMT: usize = 3234616836;
+
 #[cfg(target_pointer_width = "64")]
 pub const VIDIOC_S_FMT: usize = 3234878981;
 #[cfg(target_pointer_width = "32")]
 pub const VIDIOC_S_FMT: usize = 3234616837;
 
+#[cfg(target_pointer_width = "64")]
+pub const VIDIOC_TRY_FMT: usize = 3234879040;
+#[cfg(target_pointer_width = "32")]
+pub const VIDIOC_TRY_FMT: usize = 3234616896;
+
 #[cfg(target_pointer_width = "64")]
 pub const VIDIOC_G_EXT_CTRLS: usize = 3223344711;
 #[cfg(target_pointer_width = "32")]
@@ -1319,8 +1681,80 @@ pub const VIDIOC_S_EXT_CTRLS: usize = 3223344712;
 #[cfg(target_pointer_width = "32")]
 pub const VIDIOC_S_EXT_CTRLS: usize = 3222820424;
 
+#[cfg(target_pointer_width = "64")]
+pub const VIDIOC_TRY_EXT_CTRLS: usize = 3223344713;
+#[cfg(target_pointer_width = "32")]
+pub const VIDIOC_TRY_EXT_CTRLS: usize = 3222820425;
+
+#[cfg(target_os = "linux")]
+pub const VIDIOC_SUBSCRIBE_EVENT: usize = 1075861082;
+#[cfg(target_os = "freebsd")]
+pub const VIDIOC_SUBSCRIBE_EVENT: usize = 2149602906;
+#[cfg(target_os = "linux")]
+pub const VIDIOC_UNSUBSCRIBE_EVENT: usize = 1075861083;
+#[cfg(target_os = "freebsd")]
+pub const VIDIOC_UNSUBSCRIBE_EVENT: usize = 2149602907;
+
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+pub const VIDIOC_DQEVENT: usize = 2156418649;
+#[cfg(all(target_os = "linux", target_pointer_width = "32"))]
+pub const VIDIOC_DQEVENT: usize = 2155370073;
+#[cfg(all(target_os = "freebsd", target_pointer_width = "64"))]
+pub const VIDIOC_DQEVENT: usize = 1082676825;
+#[cfg(all(target_os = "freebsd", target_pointer_width = "32"))]
+pub const VIDIOC_DQEVENT: usize = 1081628249;
+
+/// Size of the argument, which is encoded in the request both on Linux and FreeBSD.
+pub fn request_size(request: usize) -> usize {
+    (request >> 16) & 0x1fff
+}
+
+pub fn request_name(request: usize) -> &'static str {
+    match request {
+        VIDIOC_QUERYCAP => "VIDIOC_QUERYCAP",
+        VIDIOC_ENUM_FMT => "VIDIOC_ENUM_FMT",
+        VIDIOC_ENUM_FRAMEINTERVALS => "VIDIOC_ENUM_FRAMEINTERVALS",
+        VIDIOC_ENUM_FRAMESIZES => "VIDIOC_ENUM_FRAMESIZES",
+        VIDIOC_G_CTRL => "VIDIOC_G_CTRL",
+        VIDIOC_QUERYCTRL => "VIDIOC_QUERYCTRL",
+        VIDIOC_QUERY_EXT_CTRL => "VIDIOC_QUERY_EXT_CTRL",
+        VIDIOC_QUERYMENU => "VIDIOC_QUERYMENU",
+        VIDIOC_REQBUFS => "VIDIOC_REQBUFS",
+        VIDIOC_S_PARM => "VIDIOC_S_PARM",
+        VIDIOC_STREAMOFF => "VIDIOC_STREAMOFF",
+        VIDIOC_STREAMON => "VIDIOC_STREAMON",
+        VIDIOC_DQBUF => "VIDIOC_DQBUF",
+        VIDIOC_QBUF => "VIDIOC_QBUF",
+        VIDIOC_QUERYBUF => "VIDIOC_QUERYBUF",
+        VIDIOC_G_FMT => "VIDIOC_G_FMT",
+        VIDIOC_S_FMT => "VIDIOC_S_FMT",
+        VIDIOC_TRY_FMT => "VIDIOC_TRY_FMT",
+        VIDIOC_G_EXT_CTRLS => "VIDIOC_G_EXT_CTRLS",
+        VIDIOC_S_EXT_CTRLS => "VIDIOC_S_EXT_CTRLS",
+        VIDIOC_TRY_EXT_CTRLS => "VIDIOC_TRY_EXT_CTRLS",
+        VIDIOC_SUBSCRIBE_EVENT => "VIDIOC_SUBSCRIBE_EVENT",
+        VIDIOC_UNSUBSCRIBE_EVENT => "VIDIOC_UNSUBSCRIBE_EVENT",
+        VIDIOC_DQEVENT => "VIDIOC_DQEVENT",
+        _ => "unknown ioctl",
+    }
+}
+
+#[test]
+fn test_poll_timeout() {
+    assert_eq!(poll_timeout(None), -1);
+    assert_eq!(poll_timeout(Some(Duration::ZERO)), 0);
+    assert_eq!(poll_timeout(Some(Duration::from_micros(500))), 1);
+    assert_eq!(poll_timeout(Some(Duration::from_millis(20))), 20);
+    assert_eq!(
+        poll_timeout(Some(Duration::from_secs(u64::MAX))),
+        c_int::MAX
+    );
+}
+
 #[test]
 fn test_sizes() {
+    assert_eq!(mem::size_of::<Capability>(), 104);
+
     if cfg!(target_pointer_width = "64") {
         assert_eq!(mem::size_of::<Format>(), 208);
     } else {
@@ -1348,4 +1782,13 @@ fn test_sizes() {
     } else {
         assert_eq!(mem::size_of::<ExtControls<'_>>(), 24);
     }
+
+    assert_eq!(mem::size_of::<EventSubscription>(), 32);
+    assert_eq!(mem::size_of::<EventCtrl>(), 40);
+
+    if cfg!(target_pointer_width = "64") {
+        assert_eq!(mem::size_of::<Event>(), 136);
+    } else {
+        assert_eq!(mem::size_of::<Event>(), 120);
+    }
 }